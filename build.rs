@@ -1,6 +1,16 @@
 use rustc_version::{version_meta, Channel};
 
 fn main() {
+    // Declare the channel cfg flags so that check-cfg doesn't warn about them
+    for channel in [
+        "CHANNEL_STABLE",
+        "CHANNEL_BETA",
+        "CHANNEL_NIGHTLY",
+        "CHANNEL_DEV",
+    ] {
+        println!("cargo:rustc-check-cfg=cfg({})", channel);
+    }
+
     // Set cfg flags depending on release channel
     let channel = match version_meta().unwrap().channel {
         Channel::Stable => "CHANNEL_STABLE",
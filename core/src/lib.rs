@@ -0,0 +1,362 @@
+//! `no_std` scheduling engine for [situwaition](https://docs.rs/situwaition)
+//!
+//! A [`Schedule`] keeps track of the deadline, attempts and sleeps of a situwaition, without measuring
+//! time or sleeping itself -- callers report the current time and carry out the [`Action`]s it decides on.
+//! situwaition's sync and async waiters are built on it, and [`run`] drives it with any [`Clock`] and
+//! [`Delay`] (ex. a hardware timer), so firmware gets the same retry semantics:
+//!
+//! ```
+//! use core::{cell::Cell, time::Duration};
+//!
+//! use situwaition_core::{run, Policy, TimedOut};
+//!
+//! // A mock clock, which the delay provider advances
+//! let now = Cell::new(Duration::ZERO);
+//! let clock = || now.get();
+//! let delay = |d: Duration| now.set(now.get() + d);
+//!
+//! let policy = Policy::new(Duration::from_secs(1), Duration::from_millis(100));
+//! let result = run(policy, clock, delay, || Err::<(), _>("not ready"));
+//!
+//! assert_eq!(result, Err(TimedOut("not ready")));
+//! assert_eq!(now.get(), Duration::from_millis(1100));
+//! ```
+//!
+//! No allocation is needed, so the `alloc` crate is not required.
+#![cfg_attr(not(test), no_std)]
+
+use core::time::Duration;
+
+/// Source of monotonic time, as the time since an arbitrary (fixed) point
+pub trait Clock {
+    /// The current time
+    fn now(&self) -> Duration;
+}
+
+impl<F: Fn() -> Duration> Clock for F {
+    fn now(&self) -> Duration {
+        self()
+    }
+}
+
+/// Provider of blocking delays (ex. a busy loop or a hardware timer)
+pub trait Delay {
+    /// Block for the given duration
+    fn delay(&mut self, duration: Duration);
+}
+
+impl<F: FnMut(Duration)> Delay for F {
+    fn delay(&mut self, duration: Duration) {
+        self(duration)
+    }
+}
+
+/// How long to wait, and how often to check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Policy {
+    /// Time after which waiting gives up (also the limit for a single check)
+    pub timeout: Duration,
+
+    /// Time to sleep between failed checks
+    pub check_interval: Duration,
+
+    /// Time to sleep right after a failed check, before the deadline is considered
+    pub check_cooldown: Option<Duration>,
+}
+
+impl Policy {
+    /// Policy with the given timeout and check interval, and no cooldown
+    pub const fn new(timeout: Duration, check_interval: Duration) -> Self {
+        Policy {
+            timeout,
+            check_interval,
+            check_cooldown: None,
+        }
+    }
+
+    /// Set the time to sleep right after a failed check
+    pub const fn with_cooldown(mut self, check_cooldown: Duration) -> Self {
+        self.check_cooldown = Some(check_cooldown);
+        self
+    }
+}
+
+/// What the caller of a [`Schedule`] should do next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Run the check (attempts are numbered from 1), which should be abandoned if it runs longer than `timeout`.
+    /// Report how it went with [`Schedule::succeeded`] or [`Schedule::failed`].
+    Check { attempt: u32, timeout: Duration },
+
+    /// Sleep for the given duration, then ask for the next action
+    Sleep(Duration),
+
+    /// The most recent check failed after the deadline passed, so waiting is over
+    TimedOut,
+
+    /// A check succeeded, so waiting is over
+    Done,
+}
+
+/// Where a [`Schedule`] is in its cycle of checks and sleeps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    /// About to start a new attempt
+    Start,
+
+    /// Waiting on a check to be reported
+    Checking,
+
+    /// A check failed, and the cooldown is next
+    Failed,
+
+    /// The cooldown is over (or there was none), and the deadline is next to be considered
+    CooledDown,
+
+    /// The check interval is over, and the next attempt can start
+    Slept,
+
+    /// Waiting is over
+    Finished(Action),
+}
+
+/// Bookkeeping for the deadline, attempts and sleeps of a single situwaition
+///
+/// Times passed to the schedule are durations since any fixed point (ex. the first call),
+/// as long as the same point is used throughout.
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    policy: Policy,
+    phase: Phase,
+
+    /// When the first attempt was started
+    start: Option<Duration>,
+
+    /// Attempts started so far
+    attempts: u32,
+}
+
+impl Schedule {
+    /// Start a schedule for the given policy
+    pub const fn new(policy: Policy) -> Self {
+        Schedule {
+            policy,
+            phase: Phase::Start,
+            start: None,
+            attempts: 0,
+        }
+    }
+
+    /// The policy that is being followed
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    /// Attempts that have been started so far
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Time since the first attempt was started
+    pub fn elapsed(&self, now: Duration) -> Duration {
+        self.start
+            .map(|start| now.saturating_sub(start))
+            .unwrap_or_default()
+    }
+
+    /// Decide what to do next, given the current time
+    ///
+    /// # Panics
+    ///
+    /// Panics if a check was started and has not been reported yet.
+    pub fn next(&mut self, now: Duration) -> Action {
+        match self.phase {
+            Phase::Start | Phase::Slept => {
+                self.start.get_or_insert(now);
+                self.attempts += 1;
+                self.phase = Phase::Checking;
+                Action::Check {
+                    attempt: self.attempts,
+                    timeout: self.policy.timeout,
+                }
+            }
+
+            Phase::Checking => panic!("the outcome of the check must be reported first"),
+
+            Phase::Failed => {
+                self.phase = Phase::CooledDown;
+                match self.policy.check_cooldown {
+                    Some(cooldown) => Action::Sleep(cooldown),
+                    None => self.next(now),
+                }
+            }
+
+            Phase::CooledDown => {
+                if self.elapsed(now) > self.policy.timeout {
+                    self.phase = Phase::Finished(Action::TimedOut);
+                    return Action::TimedOut;
+                }
+                self.phase = Phase::Slept;
+                Action::Sleep(self.policy.check_interval)
+            }
+
+            Phase::Finished(action) => action,
+        }
+    }
+
+    /// Report that the check that was started last succeeded
+    pub fn succeeded(&mut self) {
+        self.phase = Phase::Finished(Action::Done);
+    }
+
+    /// Report that the check that was started last failed
+    pub fn failed(&mut self) {
+        self.phase = Phase::Failed;
+    }
+}
+
+/// Error returned by [`run`] when the deadline passed, containing the error of the last check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedOut<E>(pub E);
+
+/// Run a check until it succeeds or the deadline passes, blocking with the given delay provider
+///
+/// Checks are run on the calling thread, so a check that never returns cannot be interrupted.
+pub fn run<T, E>(
+    policy: Policy,
+    clock: impl Clock,
+    mut delay: impl Delay,
+    mut check: impl FnMut() -> Result<T, E>,
+) -> Result<T, TimedOut<E>> {
+    let mut schedule = Schedule::new(policy);
+    let mut failure = None;
+
+    loop {
+        match schedule.next(clock.now()) {
+            Action::Check { .. } => match check() {
+                Ok(v) => {
+                    schedule.succeeded();
+                    return Ok(v);
+                }
+                Err(e) => {
+                    schedule.failed();
+                    failure = Some(e);
+                }
+            },
+            Action::Sleep(d) => delay.delay(d),
+            Action::TimedOut => {
+                return Err(TimedOut(failure.expect("a check failed before timing out")))
+            }
+            Action::Done => unreachable!("run returns as soon as a check succeeds"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, vec, vec::Vec};
+
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    /// Drive a schedule with a mock clock, recording every action
+    fn trace(policy: Policy, mut check: impl FnMut(u32) -> bool) -> Vec<(Duration, Action)> {
+        let mut schedule = Schedule::new(policy);
+        let mut now = Duration::ZERO;
+        let mut actions = Vec::new();
+
+        loop {
+            let action = schedule.next(now);
+            actions.push((now, action));
+            match action {
+                Action::Check { attempt, .. } => match check(attempt) {
+                    true => schedule.succeeded(),
+                    false => schedule.failed(),
+                },
+                Action::Sleep(d) => now += d,
+                Action::TimedOut | Action::Done => return actions,
+            }
+        }
+    }
+
+    #[test]
+    fn test_unit_schedule_until_success() {
+        let actions = trace(Policy::new(ms(1000), ms(100)), |attempt| attempt == 3);
+        assert_eq!(
+            actions,
+            vec![
+                (
+                    ms(0),
+                    Action::Check {
+                        attempt: 1,
+                        timeout: ms(1000)
+                    }
+                ),
+                (ms(0), Action::Sleep(ms(100))),
+                (
+                    ms(100),
+                    Action::Check {
+                        attempt: 2,
+                        timeout: ms(1000)
+                    }
+                ),
+                (ms(100), Action::Sleep(ms(100))),
+                (
+                    ms(200),
+                    Action::Check {
+                        attempt: 3,
+                        timeout: ms(1000)
+                    }
+                ),
+                (ms(200), Action::Done),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unit_schedule_cooldown_and_timeout() {
+        let actions = trace(Policy::new(ms(300), ms(100)).with_cooldown(ms(50)), |_| {
+            false
+        });
+        let checks = actions
+            .iter()
+            .filter(|(_, a)| matches!(a, Action::Check { .. }))
+            .map(|(t, _)| *t)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            checks,
+            vec![ms(0), ms(150), ms(300)],
+            "checks are separated by the cooldown and interval"
+        );
+        assert_eq!(
+            actions.last(),
+            Some(&(ms(350), Action::TimedOut)),
+            "deadline is considered after the cooldown"
+        );
+    }
+
+    #[test]
+    fn test_unit_run_with_mock_clock() {
+        let now = Cell::new(Duration::ZERO);
+        let checks = Cell::new(0);
+
+        let result = run(
+            Policy::new(Duration::from_secs(300), Duration::from_secs(10)),
+            || now.get(),
+            |d| now.set(now.get() + d),
+            || {
+                checks.set(checks.get() + 1);
+                match now.get() >= Duration::from_secs(240) {
+                    true => Ok(checks.get()),
+                    false => Err("not ready"),
+                }
+            },
+        );
+
+        assert_eq!(result, Ok(25), "check passed after 4 (mock) minutes");
+        assert_eq!(now.get(), Duration::from_secs(240));
+    }
+}
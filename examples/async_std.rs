@@ -4,7 +4,7 @@ use std::{
     time::Duration,
 };
 
-use situwaition::{runtime::async_std::wait_for, runtime::AsyncWaiter, AsyncStdAsyncSituwaition};
+use situwaition::{runtime::async_std::wait_for, runtime::AsyncWaiter};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
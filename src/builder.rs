@@ -0,0 +1,196 @@
+//! Fluent, validating builder shared by all waiters
+//!
+//! Start a builder with [`SyncWaiter::builder`][crate::sync::SyncWaiter::builder] or
+//! [`AsyncWaiter::builder`][crate::runtime::AsyncWaiter::builder]:
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use situwaition::{sync::SyncWaiter, SyncSituwaition};
+//!
+//! let result = SyncWaiter::builder(|| Ok::<_, std::io::Error>("ready"))
+//!     .name("example")
+//!     .timeout(Duration::from_secs(1))
+//!     .check_interval(Duration::from_millis(50))
+//!     .build()?
+//!     .exec();
+//! # Ok::<(), situwaition::WaiterCreationError>(())
+//! ```
+//!
+//! Options that are not set are taken from [`SituwaitionOpts::default()`], and the combination
+//! is validated when the waiter is built.
+
+use std::time::Duration;
+
+use crate::{
+    cancel::CancellationToken, SituwaitionHooks, SituwaitionOpts, SituwaitionOptsBuilder,
+    WaiterCreationError,
+};
+
+/// Builder for a waiter of type `W`, checking with `C` (a check function or future factory)
+pub struct WaiterBuilder<C, W> {
+    /// The check function (or future factory) of the waiter
+    check: C,
+
+    /// Options that have been set so far
+    opts: SituwaitionOptsBuilder,
+
+    /// Creates the waiter once the options are validated
+    make: fn(C, SituwaitionOpts) -> W,
+}
+
+impl<C, W> WaiterBuilder<C, W> {
+    pub(crate) fn new(check: C, make: fn(C, SituwaitionOpts) -> W) -> Self {
+        WaiterBuilder {
+            check,
+            opts: SituwaitionOptsBuilder::default(),
+            make,
+        }
+    }
+
+    /// Start from the given options (options set afterwards override them)
+    pub fn opts(mut self, opts: SituwaitionOpts) -> Self {
+        let SituwaitionOpts {
+            name,
+            timeout,
+            check_interval,
+            check_cooldown,
+            hooks,
+            cancellation,
+        } = opts;
+        self.opts
+            .timeout(timeout)
+            .check_interval(check_interval)
+            .check_cooldown(check_cooldown)
+            .hooks(hooks);
+        if let Some(name) = name {
+            self.opts.name(name);
+        }
+        if let Some(cancellation) = cancellation {
+            self.opts.cancellation(cancellation);
+        }
+        self
+    }
+
+    /// Set the name of the situwaition (see [`SituwaitionOpts::name`])
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.opts.name(name);
+        self
+    }
+
+    /// Set the maximum time to wait (see [`SituwaitionOpts::timeout`])
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.opts.timeout(timeout);
+        self
+    }
+
+    /// Set how often to check (see [`SituwaitionOpts::check_interval`])
+    pub fn check_interval(mut self, check_interval: Duration) -> Self {
+        self.opts.check_interval(check_interval);
+        self
+    }
+
+    /// Set the time to wait after each check (see [`SituwaitionOpts::check_cooldown`])
+    pub fn check_cooldown(mut self, check_cooldown: Duration) -> Self {
+        self.opts.check_cooldown(Some(check_cooldown));
+        self
+    }
+
+    /// Disable the cooldown after each check
+    pub fn no_check_cooldown(mut self) -> Self {
+        self.opts.check_cooldown(None);
+        self
+    }
+
+    /// Set the lifecycle hooks that will be run during the situwaition
+    pub fn hooks(mut self, hooks: SituwaitionHooks) -> Self {
+        self.opts.hooks(hooks);
+        self
+    }
+
+    /// Stop the situwaition when the given token is cancelled (see [`crate::cancel`])
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.opts.cancellation(token);
+        self
+    }
+
+    /// Validate the options and build the waiter
+    pub fn build(self) -> Result<W, WaiterCreationError> {
+        let opts = self.opts.build()?;
+        Ok((self.make)(self.check, opts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Error;
+
+    use crate::{sync::SyncWaiter, SituwaitionBase};
+
+    use super::*;
+
+    type CheckFn = fn() -> Result<(), Error>;
+
+    fn builder() -> WaiterBuilder<CheckFn, SyncWaiter<(), Error, CheckFn>> {
+        SyncWaiter::builder(|| Ok(()))
+    }
+
+    #[test]
+    fn test_unit_builder_sets_opts() {
+        let waiter = builder()
+            .name("db")
+            .timeout(Duration::from_secs(1))
+            .check_cooldown(Duration::from_millis(20))
+            .build()
+            .expect("failed to build waiter");
+        let opts = waiter.options();
+
+        assert_eq!(opts.name.as_deref(), Some("db"));
+        assert_eq!(opts.timeout, Duration::from_secs(1));
+        assert_eq!(
+            opts.check_interval,
+            SituwaitionOpts::default().check_interval,
+            "unset options use the defaults"
+        );
+        assert_eq!(opts.check_cooldown, Some(Duration::from_millis(20)));
+    }
+
+    #[test]
+    fn test_unit_builder_validates() {
+        assert!(
+            matches!(
+                builder().timeout(Duration::ZERO).build(),
+                Err(WaiterCreationError::InvalidTimeout(_))
+            ),
+            "zero timeout is rejected"
+        );
+        assert!(
+            matches!(
+                builder().check_interval(Duration::ZERO).build(),
+                Err(WaiterCreationError::InvalidInterval(_))
+            ),
+            "zero interval is rejected"
+        );
+        assert!(
+            matches!(
+                builder().timeout(Duration::from_millis(100)).build(),
+                Err(WaiterCreationError::InvalidTimeout(_))
+            ),
+            "timeout shorter than the default interval is blamed on the timeout"
+        );
+        assert!(
+            matches!(
+                builder().check_interval(Duration::from_secs(60)).build(),
+                Err(WaiterCreationError::InvalidInterval(_))
+            ),
+            "interval longer than the default timeout is blamed on the interval"
+        );
+        assert!(
+            matches!(
+                builder().check_cooldown(Duration::from_secs(60)).build(),
+                Err(WaiterCreationError::InvalidCooldown(_))
+            ),
+            "cooldown longer than the timeout is rejected"
+        );
+    }
+}
@@ -0,0 +1,237 @@
+//! Cancelling situwaitions from the outside
+//!
+//! A [`CancellationToken`] can be attached to a situwaition (ex. with
+//! [`AsyncWaiter::with_cancellation`][crate::runtime::AsyncWaiter::with_cancellation]
+//! or [`SituwaitionOpts::cancellation`][crate::SituwaitionOpts::cancellation]). Cancelling the token (or any clone of it) stops the situwaition
+//! between or during attempts, which then finishes with [`SituwaitionError::Cancelled`][crate::SituwaitionError::Cancelled]:
+//!
+//! ```
+//! use situwaition::{
+//!     cancel::CancellationToken, clock::MockClock, runtime::AsyncWaiter, SituwaitionError,
+//! };
+//!
+//! # futures_executor::block_on(async {
+//! let token = CancellationToken::new();
+//! token.cancel();
+//!
+//! let result = AsyncWaiter::from_factory(|| async { Ok::<_, std::io::Error>(()) })
+//!     .with_cancellation(token)
+//!     .with_runtime(MockClock::new())
+//!     .await;
+//! assert!(matches!(result, Err(SituwaitionError::Cancelled)));
+//! # })
+//! ```
+//!
+//! The token does not depend on any executor. For async situwaitions, a check that is running when the
+//! token is cancelled is dropped right away. Sync situwaitions finish right away too, but cannot interrupt a running check (no further checks are started).
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    task::{Context, Poll, Waker},
+};
+
+/// Shared state of a [`CancellationToken`] and its clones
+#[derive(Default)]
+struct Inner {
+    cancelled: AtomicBool,
+
+    /// Wakers of the [`WaitForCancellation`] futures that are pending, by ID
+    waiters: Mutex<(u64, BTreeMap<u64, Waker>)>,
+}
+
+/// Token that cancels the situwaitions it is attached to
+///
+/// Clones share the same state, so cancelling any clone cancels them all.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+impl fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+impl CancellationToken {
+    /// Create a token that has not been cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the token, waking everything that is waiting on it
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        let wakers = std::mem::take(&mut self.waiters().1);
+        for waker in wakers.into_values() {
+            waker.wake();
+        }
+    }
+
+    /// Whether the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Wait until the token is cancelled
+    pub fn cancelled(&self) -> WaitForCancellation {
+        WaitForCancellation {
+            token: self.clone(),
+            id: None,
+        }
+    }
+
+    fn waiters(&self) -> MutexGuard<'_, (u64, BTreeMap<u64, Waker>)> {
+        self.inner.waiters.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Future that completes once a [`CancellationToken`] is cancelled, produced by [`CancellationToken::cancelled`]
+///
+/// Dropping the future unregisters it from the token.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[derive(Debug)]
+pub struct WaitForCancellation {
+    token: CancellationToken,
+
+    /// ID of the waker registered with the token (if any)
+    id: Option<u64>,
+}
+
+impl Future for WaitForCancellation {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let token = self.token.clone();
+        if token.is_cancelled() {
+            return Poll::Ready(());
+        }
+
+        // The flag is checked again under the lock, so a concurrent cancel either sees this waker or is seen here
+        let mut waiters = token.waiters();
+        if token.is_cancelled() {
+            return Poll::Ready(());
+        }
+        let (next_id, wakers) = &mut *waiters;
+        let id = *self.id.get_or_insert_with(|| {
+            *next_id += 1;
+            *next_id
+        });
+        wakers.insert(id, cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for WaitForCancellation {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            self.token.waiters().1.remove(&id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::IntoFuture, io::ErrorKind, task::Wake, time::Duration};
+
+    use crate::{
+        clock::MockClock, runtime::AsyncWaiter, sync::SyncWaiter, SituwaitionError, SyncSituwaition,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_unit_cancellation_wakes_waiters() {
+        let token = CancellationToken::new();
+        let waiter = std::thread::spawn({
+            let token = token.clone();
+            move || futures_executor::block_on(token.cancelled())
+        });
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(!token.is_cancelled());
+        token.cancel();
+        waiter.join().expect("waiting thread was woken");
+        assert!(token.clone().is_cancelled(), "clones share cancellation");
+    }
+
+    #[test]
+    fn test_unit_dropped_waiters_are_unregistered() {
+        let token = CancellationToken::new();
+        let mut waiting = Box::pin(token.cancelled());
+
+        struct Noop;
+        impl Wake for Noop {
+            fn wake(self: Arc<Self>) {}
+        }
+        let waker = Waker::from(Arc::new(Noop));
+        let mut cx = Context::from_waker(&waker);
+        assert!(waiting.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(token.waiters().1.len(), 1, "pending future is registered");
+        drop(waiting);
+        assert!(
+            token.waiters().1.is_empty(),
+            "dropped future is unregistered"
+        );
+    }
+
+    #[test]
+    fn test_unit_sync_cancellation() {
+        let token = CancellationToken::new();
+        let canceller = std::thread::spawn({
+            let token = token.clone();
+            move || {
+                std::thread::sleep(Duration::from_millis(50));
+                token.cancel();
+            }
+        });
+
+        let result = SyncWaiter::with_check_interval(
+            || Err::<(), _>(std::io::Error::new(ErrorKind::Other, "not ready")),
+            Duration::from_millis(10),
+        )
+        .expect("failed to create")
+        .with_cancellation(token)
+        .exec();
+        canceller.join().expect("canceller finished");
+
+        assert!(
+            matches!(result, Err(SituwaitionError::Cancelled)),
+            "sync situwaition stops once cancelled"
+        );
+    }
+
+    #[test]
+    fn test_unit_async_cancellation_during_check() {
+        let token = CancellationToken::new();
+        let canceller = std::thread::spawn({
+            let token = token.clone();
+            move || {
+                std::thread::sleep(Duration::from_millis(50));
+                token.cancel();
+            }
+        });
+
+        let result = futures_executor::block_on(
+            AsyncWaiter::from_factory(std::future::pending::<Result<(), std::io::Error>>)
+                .with_cancellation(token)
+                .with_runtime(MockClock::new())
+                .into_future(),
+        );
+        canceller.join().expect("canceller finished");
+
+        assert!(
+            matches!(result, Err(SituwaitionError::Cancelled)),
+            "check that never finishes is interrupted by cancellation"
+        );
+    }
+}
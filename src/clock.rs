@@ -0,0 +1,714 @@
+//! Clocks used to measure time and sleep while waiting
+//!
+//! [`SyncWaiter`][crate::sync::SyncWaiter]s use the [`SystemClock`] unless given another [`Clock`] with
+//! [`with_clock`][crate::sync::SyncWaiter::with_clock]. [`MockClock`] only moves when it is slept on or
+//! [advanced][MockClock::advance] manually, so code that waits on situwaitions can be tested instantly
+//! and deterministically:
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use situwaition::{clock::MockClock, sync::SyncWaiter, SyncSituwaition};
+//!
+//! let clock = MockClock::new();
+//! let check_clock = clock.clone();
+//!
+//! // Simulate a service that takes 4 minutes to come up, against a 5 minute timeout
+//! let result = SyncWaiter::builder(move || match check_clock.elapsed() >= Duration::from_secs(240) {
+//!     true => Ok("up"),
+//!     false => Err(std::io::Error::new(std::io::ErrorKind::Other, "down")),
+//! })
+//! .timeout(Duration::from_secs(300))
+//! .check_interval(Duration::from_secs(10))
+//! .check_cooldown(Duration::from_secs(10))
+//! .build()?
+//! .with_clock(clock.clone())
+//! .exec();
+//!
+//! assert!(matches!(result, Ok("up")));
+//! assert_eq!(clock.elapsed(), Duration::from_secs(240));
+//! # Ok::<(), situwaition::WaiterCreationError>(())
+//! ```
+//!
+//! Every wait of a sync situwaition is measured on its clock, including the wait for a check that is still
+//! running (see [`Clock::park_until`]), so a check that hangs is abandoned as soon as mock time passes the timeout.
+//!
+//! [`MockClock`] is also a [`Runtime`], so it can be used with
+//! [`AsyncWaiter::with_runtime`][crate::runtime::AsyncWaiter::with_runtime] on any executor.
+//!
+//! [`Instant`] (and so the [`SystemClock`]) stops while the system is suspended, so a machine that sleeps
+//! mid-wait extends the deadline. On Linux, the `boottime` feature adds `BootTimeClock` (and the `BootTime`
+//! runtime wrapper for async waiters), which measure time with `CLOCK_BOOTTIME` and so include time spent suspended.
+//! Waits that are not done with `clock_nanosleep` are split into slices of at most a second, so they end
+//! at most a second late after a resume.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll, Wake, Waker},
+    thread::Thread,
+    time::{Duration, Instant},
+};
+
+#[cfg(all(feature = "boottime", target_os = "linux"))]
+use pin_project_lite::pin_project;
+
+use crate::runtime::{Runtime, Timeout};
+
+/// Source of time for synchronous waiting
+pub trait Clock: Send + Sync {
+    /// The current time, according to the clock
+    fn now(&self) -> Instant;
+
+    /// Block the current thread for the given duration
+    fn sleep(&self, duration: Duration);
+
+    /// Block the current thread until the clock reaches `deadline`, or until the thread is
+    /// [unparked][Thread::unpark] (whichever comes first). Unlike [`Clock::sleep`], this must not move the clock.
+    ///
+    /// The default implementation parks the thread for the time left until the deadline.
+    fn park_until(&self, deadline: Instant) {
+        std::thread::park_timeout(deadline.saturating_duration_since(self.now()))
+    }
+}
+
+/// Waker that unparks a thread, so that threads parked on a [`Clock`] can wait on futures and mock time
+pub(crate) struct ThreadWaker(pub(crate) Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark()
+    }
+}
+
+/// The system clock (via [`Instant::now`] and [`std::thread::sleep`])
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration)
+    }
+}
+
+/// Clock that only moves when it is slept on or advanced manually
+///
+/// Clones share the same time, so a clone can be handed to a waiter while the test keeps another.
+/// Sleeping on the clock advances it immediately, except for check timeouts (see [`Runtime::timeout`] and
+/// [`Clock::park_until`]), which only expire once the clock is advanced past them (ex. by the check itself).
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    inner: Arc<MockClockInner>,
+}
+
+#[derive(Debug)]
+struct MockClockInner {
+    /// The (real) time the clock was created at, which mock time is measured from
+    origin: Instant,
+
+    /// Mock time elapsed since the clock was created, and the tasks waiting on it to advance
+    state: Mutex<(Duration, Vec<Waker>)>,
+}
+
+impl MockClock {
+    /// Create a mock clock, which starts at the current time
+    pub fn new() -> Self {
+        MockClock {
+            inner: Arc::new(MockClockInner {
+                origin: Instant::now(),
+                state: Mutex::new((Duration::ZERO, Vec::new())),
+            }),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, (Duration, Vec<Waker>)> {
+        self.inner.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Mock time that has passed since the clock was created
+    pub fn elapsed(&self) -> Duration {
+        self.state().0
+    }
+
+    /// Move the clock forward, waking any check timeouts that are waiting on it
+    pub fn advance(&self, duration: Duration) {
+        let wakers = {
+            let mut state = self.state();
+            state.0 += duration;
+            std::mem::take(&mut state.1)
+        };
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    /// Move the clock forward to the given time, if it is not already past it
+    fn advance_to(&self, deadline: Instant) {
+        let now = Clock::now(self);
+        if deadline > now {
+            self.advance(deadline - now);
+        }
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        MockClock::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.inner.origin + self.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration)
+    }
+
+    /// Parks until the clock is advanced past the deadline (ex. by a check, or manually), or the thread is unparked
+    fn park_until(&self, deadline: Instant) {
+        {
+            let mut state = self.state();
+            if self.inner.origin + state.0 >= deadline {
+                return;
+            }
+            let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+            state.1.push(waker);
+        }
+        std::thread::park();
+    }
+}
+
+impl Runtime for MockClock {
+    type Sleep = MockSleep;
+
+    fn now(&self) -> Instant {
+        Clock::now(self)
+    }
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        MockSleep {
+            clock: self.clone(),
+            deadline: Clock::now(self) + duration,
+            advance: true,
+        }
+    }
+
+    fn timeout<F: Future>(&self, duration: Duration, future: F) -> Timeout<F, Self::Sleep> {
+        let deadline = MockSleep {
+            clock: self.clone(),
+            deadline: Clock::now(self) + duration,
+            advance: false,
+        };
+        Timeout::new(future, deadline)
+    }
+}
+
+/// Sleep on a [`MockClock`]
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct MockSleep {
+    clock: MockClock,
+    deadline: Instant,
+
+    /// Whether polling advances the clock to the deadline (rather than waiting for it to be advanced)
+    advance: bool,
+}
+
+impl Future for MockSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.advance {
+            self.clock.advance_to(self.deadline);
+            return Poll::Ready(());
+        }
+
+        let mut state = self.clock.state();
+        if self.clock.inner.origin + state.0 >= self.deadline {
+            return Poll::Ready(());
+        }
+        state.1.push(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// Clock that keeps counting while the system is suspended, via `CLOCK_BOOTTIME`
+///
+/// Instants produced by this clock are only comparable with each other (not with [`Instant::now`]).
+#[cfg(all(feature = "boottime", target_os = "linux"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "boottime", target_os = "linux"))))]
+#[derive(Debug, Clone, Copy)]
+pub struct BootTimeClock {
+    /// When the clock was created, which boot time is measured from
+    origin: Instant,
+
+    /// Boot time when the clock was created
+    origin_boottime: Duration,
+}
+
+#[cfg(all(feature = "boottime", target_os = "linux"))]
+impl BootTimeClock {
+    /// Create a clock based on the current boot time
+    pub fn new() -> Self {
+        BootTimeClock {
+            origin: Instant::now(),
+            origin_boottime: boottime(),
+        }
+    }
+}
+
+#[cfg(all(feature = "boottime", target_os = "linux"))]
+impl Default for BootTimeClock {
+    fn default() -> Self {
+        BootTimeClock::new()
+    }
+}
+
+#[cfg(all(feature = "boottime", target_os = "linux"))]
+impl Clock for BootTimeClock {
+    fn now(&self) -> Instant {
+        self.origin + boottime().saturating_sub(self.origin_boottime)
+    }
+
+    fn sleep(&self, duration: Duration) {
+        let deadline = to_timespec(boottime() + duration);
+        loop {
+            // SAFETY: deadline is a valid timespec, and the remaining time is not requested
+            let ret = unsafe {
+                libc::clock_nanosleep(
+                    libc::CLOCK_BOOTTIME,
+                    libc::TIMER_ABSTIME,
+                    &deadline,
+                    std::ptr::null_mut(),
+                )
+            };
+            // Sleeping to an absolute deadline can simply be retried after an interruption
+            if ret != libc::EINTR {
+                break;
+            }
+        }
+    }
+
+    fn park_until(&self, deadline: Instant) {
+        // Parking is measured on the monotonic clock, so park in slices to notice a resume
+        std::thread::park_timeout(
+            deadline
+                .saturating_duration_since(self.now())
+                .min(BOOTTIME_SLICE),
+        )
+    }
+}
+
+/// Longest wait on the monotonic clock (which stops while suspended) before boot time is checked again
+#[cfg(all(feature = "boottime", target_os = "linux"))]
+const BOOTTIME_SLICE: Duration = Duration::from_secs(1);
+
+/// Time since boot (including time spent suspended)
+#[cfg(all(feature = "boottime", target_os = "linux"))]
+fn boottime() -> Duration {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: ts is a valid timespec to write to
+    let ret = unsafe { libc::clock_gettime(libc::CLOCK_BOOTTIME, &mut ts) };
+    assert_eq!(ret, 0, "clock_gettime(CLOCK_BOOTTIME) failed");
+    Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+}
+
+#[cfg(all(feature = "boottime", target_os = "linux"))]
+fn to_timespec(d: Duration) -> libc::timespec {
+    libc::timespec {
+        tv_sec: d.as_secs() as libc::time_t,
+        tv_nsec: d.subsec_nanos() as libc::c_long,
+    }
+}
+
+/// [`Runtime`] that measures time with a [`BootTimeClock`], sleeping on another runtime
+///
+/// The overall deadline, per-check timeouts, cooldowns and intervals all include time spent suspended.
+/// Sleeps end by a boot-time deadline: the wrapped runtime's sleeps are split into slices of at most a second,
+/// so a sleep that spans a suspend ends at most a second after its deadline once the system resumes.
+///
+/// ```
+/// # #[cfg(feature = "tokio")]
+/// # {
+/// use situwaition::{clock::BootTime, runtime::{tokio::TokioRuntime, AsyncWaiter}};
+///
+/// let waiter = AsyncWaiter::from_factory(|| async { Ok::<_, std::io::Error>(()) })
+///     .with_runtime(BootTime::new(TokioRuntime));
+/// # }
+/// ```
+#[cfg(all(feature = "boottime", target_os = "linux"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "boottime", target_os = "linux"))))]
+#[derive(Debug, Clone, Copy)]
+pub struct BootTime<Rt> {
+    clock: BootTimeClock,
+    runtime: Rt,
+}
+
+#[cfg(all(feature = "boottime", target_os = "linux"))]
+impl<Rt: Runtime> BootTime<Rt> {
+    /// Measure deadlines with boot time, sleeping on the given runtime
+    pub fn new(runtime: Rt) -> Self {
+        BootTime {
+            clock: BootTimeClock::new(),
+            runtime,
+        }
+    }
+}
+
+#[cfg(all(feature = "boottime", target_os = "linux"))]
+impl<Rt: Runtime + Clone> Runtime for BootTime<Rt> {
+    type Sleep = BootTimeSleep<Rt>;
+
+    fn now(&self) -> Instant {
+        Clock::now(&self.clock)
+    }
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        BootTimeSleep {
+            runtime: self.runtime.clone(),
+            clock: self.clock,
+            deadline: Clock::now(&self.clock) + duration,
+            slice: self.runtime.sleep(duration.min(BOOTTIME_SLICE)),
+        }
+    }
+}
+
+#[cfg(all(feature = "boottime", target_os = "linux"))]
+pin_project! {
+    /// Sleep until a boot-time deadline, produced by [`BootTime`]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "boottime", target_os = "linux"))))]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct BootTimeSleep<Rt>
+    where
+        Rt: Runtime,
+    {
+        runtime: Rt,
+        clock: BootTimeClock,
+        deadline: Instant,
+
+        // Sleep on the wrapped runtime, after which boot time is checked again
+        #[pin]
+        slice: Rt::Sleep,
+    }
+}
+
+#[cfg(all(feature = "boottime", target_os = "linux"))]
+impl<Rt: Runtime> Future for BootTimeSleep<Rt> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut this = self.project();
+        loop {
+            if this.slice.as_mut().poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+            let remaining = this
+                .deadline
+                .saturating_duration_since(Clock::now(this.clock));
+            if remaining.is_zero() {
+                return Poll::Ready(());
+            }
+            this.slice
+                .set(this.runtime.sleep(remaining.min(BOOTTIME_SLICE)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Error, ErrorKind};
+
+    use futures_executor::block_on;
+
+    use std::sync::mpsc;
+
+    use crate::{
+        cancel::CancellationToken, runtime::AsyncWaiter, sync::SyncWaiter, SituwaitionError,
+        SyncSituwaition,
+    };
+
+    use super::*;
+
+    fn not_ready() -> Error {
+        Error::new(ErrorKind::Other, "not ready")
+    }
+
+    #[test]
+    fn test_unit_mock_clock_sync_timeout() {
+        let clock = MockClock::new();
+        let real_start = Instant::now();
+
+        let result = SyncWaiter::builder(|| Err::<(), _>(not_ready()))
+            .timeout(Duration::from_secs(300))
+            .check_interval(Duration::from_secs(10))
+            .check_cooldown(Duration::from_secs(10))
+            .build()
+            .expect("failed to create")
+            .with_clock(clock.clone())
+            .exec();
+
+        assert!(
+            matches!(result, Err(SituwaitionError::TimeoutError(..))),
+            "failing check times out"
+        );
+        assert_eq!(
+            clock.elapsed(),
+            Duration::from_secs(300) + Duration::from_millis(1),
+            "mock time passed until just after the timeout"
+        );
+        assert!(
+            real_start.elapsed() < Duration::from_secs(5),
+            "no real time was spent waiting"
+        );
+    }
+
+    #[test]
+    fn test_unit_mock_clock_sync_check_timeout() {
+        let clock = MockClock::new();
+        let check_clock = clock.clone();
+
+        let result = SyncWaiter::builder(move || {
+            // The check takes (mock) time longer than the timeout, and doesn't return for a while
+            check_clock.advance(Duration::from_secs(60));
+            std::thread::sleep(Duration::from_millis(500));
+            Ok::<_, Error>(())
+        })
+        .timeout(Duration::from_secs(30))
+        .check_interval(Duration::from_millis(10))
+        .build()
+        .expect("failed to create")
+        .with_clock(clock)
+        .exec();
+
+        assert!(
+            matches!(result, Err(SituwaitionError::CheckTimeoutError(_))),
+            "check that outlasts the timeout times out"
+        );
+    }
+
+    /// Check that blocks (in real time) until it is released
+    fn blocking_check() -> (
+        mpsc::Sender<()>,
+        impl Fn() -> Result<(), Error> + Send + Sync,
+    ) {
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let release_rx = Mutex::new(release_rx);
+        let check = move || {
+            let _ = release_rx.lock().unwrap().recv();
+            Ok(())
+        };
+        (release_tx, check)
+    }
+
+    #[test]
+    fn test_unit_mock_clock_sync_waits_on_mock_time() {
+        let clock = MockClock::new();
+        let (release, check) = blocking_check();
+
+        let mut handle = SyncWaiter::builder(check)
+            .timeout(Duration::from_secs(300))
+            .check_interval(Duration::from_secs(10))
+            .build()
+            .expect("failed to create")
+            .with_clock(clock.clone())
+            .spawn();
+        while handle.progress().attempts == 0 {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        assert!(
+            handle.join_timeout(Duration::from_millis(50)).is_none(),
+            "real time does not count towards the timeout"
+        );
+        clock.advance(Duration::from_secs(301));
+        assert!(
+            matches!(
+                handle.join_timeout(Duration::from_secs(1)),
+                Some(Err(SituwaitionError::CheckTimeoutError(_)))
+            ),
+            "check times out as soon as mock time passes the timeout"
+        );
+        drop(release);
+    }
+
+    #[test]
+    fn test_unit_mock_clock_sync_cancel() {
+        let clock = MockClock::new();
+        let (release, check) = blocking_check();
+        let token = CancellationToken::new();
+
+        let mut handle = SyncWaiter::builder(check)
+            .timeout(Duration::from_secs(300))
+            .check_interval(Duration::from_secs(10))
+            .build()
+            .expect("failed to create")
+            .with_clock(clock)
+            .with_cancellation(token.clone())
+            .spawn();
+        while handle.progress().attempts == 0 {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        token.cancel();
+        assert!(
+            matches!(
+                handle.join_timeout(Duration::from_secs(1)),
+                Some(Err(SituwaitionError::Cancelled))
+            ),
+            "cancellation does not wait for mock time to pass"
+        );
+        drop(release);
+    }
+
+    #[test]
+    fn test_unit_mock_clock_async() {
+        let clock = MockClock::new();
+        let check_clock = clock.clone();
+
+        let result = block_on(
+            AsyncWaiter::builder(|| {
+                let clock = check_clock.clone();
+                async move {
+                    match clock.elapsed() >= Duration::from_secs(240) {
+                        true => Ok(clock.elapsed()),
+                        false => Err(not_ready()),
+                    }
+                }
+            })
+            .timeout(Duration::from_secs(300))
+            .check_interval(Duration::from_secs(10))
+            .build()
+            .expect("failed to create")
+            .with_runtime(clock.clone())
+            .exec(),
+        );
+
+        assert!(
+            matches!(result, Ok(d) if d == Duration::from_secs(240)),
+            "check passed once enough mock time had passed"
+        );
+    }
+
+    #[test]
+    fn test_unit_mock_clock_timeout_waits_for_advance() {
+        let clock = MockClock::new();
+        let mut timeout =
+            Box::pin(clock.timeout(Duration::from_secs(5), std::future::pending::<()>()));
+
+        let pending = block_on(std::future::poll_fn(|cx| {
+            Poll::Ready(timeout.as_mut().poll(cx).is_pending())
+        }));
+        assert!(pending, "timeout does not advance the clock by itself");
+
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(
+            block_on(timeout),
+            Err(crate::runtime::Elapsed),
+            "timeout expires once the clock is advanced"
+        );
+    }
+
+    #[cfg(all(feature = "boottime", target_os = "linux"))]
+    #[test]
+    fn test_unit_boottime_clock() {
+        let clock = BootTimeClock::new();
+        let start = clock.now();
+        clock.sleep(Duration::from_millis(50));
+        let elapsed = clock.now() - start;
+        assert!(
+            elapsed >= Duration::from_millis(50) && elapsed < Duration::from_secs(5),
+            "boot time clock measures sleeps ({elapsed:?})"
+        );
+
+        let result = SyncWaiter::builder(|| Err::<(), _>(not_ready()))
+            .timeout(Duration::from_millis(100))
+            .check_interval(Duration::from_millis(10))
+            .build()
+            .expect("failed to create")
+            .with_clock(clock)
+            .exec();
+        assert!(
+            matches!(result, Err(SituwaitionError::TimeoutError(..))),
+            "sync waiter times out on boot time"
+        );
+    }
+
+    /// Runtime whose sleeps end right away, recording how long they were meant to be
+    #[cfg(all(feature = "boottime", target_os = "linux"))]
+    #[derive(Clone, Default)]
+    struct EagerRuntime(Arc<Mutex<Vec<Duration>>>);
+
+    #[cfg(all(feature = "boottime", target_os = "linux"))]
+    impl Runtime for EagerRuntime {
+        type Sleep = std::future::Ready<()>;
+
+        fn now(&self) -> Instant {
+            Instant::now()
+        }
+
+        fn sleep(&self, duration: Duration) -> Self::Sleep {
+            self.0.lock().unwrap().push(duration);
+            std::future::ready(())
+        }
+    }
+
+    #[cfg(all(feature = "boottime", target_os = "linux"))]
+    #[test]
+    fn test_unit_boottime_sleep_until_deadline() {
+        let inner = EagerRuntime::default();
+        let runtime = BootTime::new(inner.clone());
+
+        drop(runtime.sleep(Duration::from_secs(3600)));
+        assert_eq!(
+            *inner.0.lock().unwrap(),
+            vec![BOOTTIME_SLICE],
+            "long sleeps are split into slices"
+        );
+        inner.0.lock().unwrap().clear();
+
+        let start = runtime.now();
+        block_on(runtime.sleep(Duration::from_millis(20)));
+        assert!(
+            runtime.now() - start >= Duration::from_millis(20),
+            "sleep lasted until the boot time deadline"
+        );
+        assert!(
+            inner.0.lock().unwrap().len() > 1,
+            "wrapped sleeps that ended early were restarted"
+        );
+
+        let result =
+            block_on(runtime.timeout(Duration::from_millis(20), std::future::pending::<()>()));
+        assert!(result.is_err(), "timeouts end on boot time");
+        assert!(runtime.now() - start >= Duration::from_millis(40));
+    }
+
+    #[cfg(all(feature = "boottime", feature = "tokio", target_os = "linux"))]
+    #[tokio::test]
+    async fn test_unit_boottime_async() {
+        let start = Instant::now();
+        let result = AsyncWaiter::builder(|| async { Err::<(), _>(not_ready()) })
+            .timeout(Duration::from_millis(100))
+            .check_interval(Duration::from_millis(10))
+            .build()
+            .expect("failed to create")
+            .with_runtime(BootTime::new(crate::runtime::tokio::TokioRuntime))
+            .await;
+        assert!(
+            matches!(result, Err(SituwaitionError::TimeoutError(..))),
+            "async waiter times out on boot time"
+        );
+        assert!(
+            start.elapsed() >= Duration::from_millis(100),
+            "waited until the deadline"
+        );
+    }
+}
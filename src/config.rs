@@ -0,0 +1,161 @@
+//! Loading [`SituwaitionOpts`] from configuration
+//!
+//! With the `serde` feature, [`SituwaitionOpts`] can be (de)serialized, with durations written
+//! in a human readable format (ex. "3s", "250ms"):
+//!
+//! ```toml
+//! timeout = "30s"
+//! check_interval = "500ms"
+//! ```
+//!
+//! With the `env` feature, options can be overridden with `SITUWAITION_*` environment variables
+//! (see [`SituwaitionOpts::with_env_overrides`]).
+#![cfg(any(feature = "env", feature = "serde"))]
+
+#[cfg(feature = "env")]
+use std::time::Duration;
+
+#[cfg(feature = "env")]
+use crate::{SituwaitionOpts, WaiterCreationError};
+
+/// Environment variable that overrides [`SituwaitionOpts::timeout`]
+#[cfg(feature = "env")]
+pub const ENV_TIMEOUT: &str = "SITUWAITION_TIMEOUT";
+
+/// Environment variable that overrides [`SituwaitionOpts::check_interval`]
+#[cfg(feature = "env")]
+pub const ENV_CHECK_INTERVAL: &str = "SITUWAITION_CHECK_INTERVAL";
+
+/// Environment variable that overrides [`SituwaitionOpts::check_cooldown`] (`none` disables the cooldown)
+#[cfg(feature = "env")]
+pub const ENV_CHECK_COOLDOWN: &str = "SITUWAITION_CHECK_COOLDOWN";
+
+/// Read a human readable duration (ex. "3s", "250ms") from an environment variable, if present
+#[cfg(feature = "env")]
+fn read_duration(
+    name: &str,
+    to_err: impl Fn(String) -> WaiterCreationError,
+) -> Result<Option<Duration>, WaiterCreationError> {
+    match std::env::var(name) {
+        Ok(v) => humantime::parse_duration(v.trim())
+            .map(Some)
+            .map_err(|e| to_err(format!("failed to parse {name} value [{v}]: {e}"))),
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(e) => Err(to_err(format!("failed to read {name}: {e}"))),
+    }
+}
+
+#[cfg(feature = "env")]
+impl SituwaitionOpts {
+    /// Build options from the defaults, overridden by `SITUWAITION_*` environment variables
+    /// (see [`SituwaitionOpts::with_env_overrides`])
+    pub fn from_env() -> Result<Self, WaiterCreationError> {
+        SituwaitionOpts::default().with_env_overrides()
+    }
+
+    /// Override options with values from the following environment variables, if they are set:
+    ///
+    /// - `SITUWAITION_TIMEOUT` (ex. "3s")
+    /// - `SITUWAITION_CHECK_INTERVAL` (ex. "250ms")
+    /// - `SITUWAITION_CHECK_COOLDOWN` (ex. "1s", or "none")
+    ///
+    /// The resulting options are [validated][SituwaitionOpts::validate].
+    pub fn with_env_overrides(mut self) -> Result<Self, WaiterCreationError> {
+        if let Some(timeout) = read_duration(ENV_TIMEOUT, WaiterCreationError::InvalidTimeout)? {
+            self.timeout = timeout;
+        }
+
+        if let Some(interval) =
+            read_duration(ENV_CHECK_INTERVAL, WaiterCreationError::InvalidInterval)?
+        {
+            self.check_interval = interval;
+        }
+
+        match std::env::var(ENV_CHECK_COOLDOWN).as_deref().map(str::trim) {
+            Ok("none") => self.check_cooldown = None,
+            _ => {
+                if let Some(cooldown) =
+                    read_duration(ENV_CHECK_COOLDOWN, WaiterCreationError::InvalidCooldown)?
+                {
+                    self.check_cooldown = Some(cooldown);
+                }
+            }
+        }
+
+        self.validate()?;
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::SituwaitionOpts;
+
+    #[cfg(feature = "env")]
+    use super::*;
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_unit_opts_serde_human_durations() {
+        let opts: SituwaitionOpts =
+            serde_json::from_str(r#"{"timeout": "1m 30s", "check_cooldown": "250ms"}"#)
+                .expect("failed to deserialize");
+        assert_eq!(opts.timeout, Duration::from_secs(90));
+        assert_eq!(
+            opts.check_interval,
+            SituwaitionOpts::default().check_interval,
+            "missing fields are defaulted"
+        );
+        assert_eq!(opts.check_cooldown, Some(Duration::from_millis(250)));
+
+        let json = serde_json::to_value(&opts).expect("failed to serialize");
+        assert_eq!(json["timeout"], "1m 30s");
+        assert_eq!(json["check_interval"], "250ms");
+    }
+
+    /// Environment variables are process-wide, so all env-related checks live in a single test
+    #[cfg(feature = "env")]
+    #[test]
+    fn test_unit_opts_with_env_overrides() {
+        std::env::set_var(ENV_TIMEOUT, "5s");
+        std::env::set_var(ENV_CHECK_INTERVAL, "100ms");
+        std::env::set_var(ENV_CHECK_COOLDOWN, "1s 500ms");
+        let opts = SituwaitionOpts::from_env().expect("failed to load opts from env");
+        assert_eq!(opts.timeout, Duration::from_secs(5));
+        assert_eq!(opts.check_interval, Duration::from_millis(100));
+        assert_eq!(opts.check_cooldown, Some(Duration::from_millis(1_500)));
+
+        std::env::set_var(ENV_CHECK_COOLDOWN, "none");
+        let opts = SituwaitionOpts {
+            check_cooldown: Some(Duration::from_secs(1)),
+            ..SituwaitionOpts::default()
+        }
+        .with_env_overrides()
+        .expect("failed to load opts from env");
+        assert_eq!(opts.check_cooldown, None, "cooldown can be disabled");
+
+        std::env::set_var(ENV_TIMEOUT, "soon");
+        assert!(
+            matches!(
+                SituwaitionOpts::from_env(),
+                Err(WaiterCreationError::InvalidTimeout(_))
+            ),
+            "unparseable durations are rejected"
+        );
+
+        std::env::set_var(ENV_TIMEOUT, "50ms");
+        assert!(
+            matches!(
+                SituwaitionOpts::from_env(),
+                Err(WaiterCreationError::InvalidTimeout(_))
+            ),
+            "timeouts shorter than the interval are rejected"
+        );
+
+        std::env::remove_var(ENV_TIMEOUT);
+        std::env::remove_var(ENV_CHECK_INTERVAL);
+        std::env::remove_var(ENV_CHECK_COOLDOWN);
+    }
+}
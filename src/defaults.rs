@@ -0,0 +1,156 @@
+//! Process-wide default options and named presets
+//!
+//! [`SituwaitionOpts::default()`] (and so [`wait_for`][crate::wait_for], `from_fn` and `from_factory`)
+//! uses the built-in defaults (a 3s timeout, checked every 250ms) unless they are overridden for the process,
+//! usually once at startup:
+//!
+//! ```
+//! use situwaition::defaults::{self, Preset};
+//!
+//! defaults::set_preset(Preset::IntegrationTest);
+//! # defaults::reset();
+//! ```
+//!
+//! Overriding defaults does not affect situwaitions that were already created.
+
+use std::{
+    sync::{RwLock, RwLockReadGuard, RwLockWriteGuard},
+    time::Duration,
+};
+
+use crate::{SituwaitionOpts, WaiterCreationError};
+
+/// Default options set via [`set_default_opts`] or [`set_preset`]
+static DEFAULT_OPTS: RwLock<Option<SituwaitionOpts>> = RwLock::new(None);
+
+/// Named sets of options for common situations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    /// Quick checks against in-process state (500ms timeout, checked every 10ms)
+    FastUnitTest,
+
+    /// Waiting on local services like databases and containers (30s timeout, checked every 500ms)
+    IntegrationTest,
+
+    /// Waiting on dependencies while a service starts up (2 minute timeout, checked every 2s, with a 1s cooldown)
+    ProductionStartup,
+}
+
+impl Preset {
+    /// The options for this preset
+    pub fn opts(&self) -> SituwaitionOpts {
+        let (timeout, check_interval, check_cooldown) = match self {
+            Preset::FastUnitTest => (Duration::from_millis(500), Duration::from_millis(10), None),
+            Preset::IntegrationTest => (Duration::from_secs(30), Duration::from_millis(500), None),
+            Preset::ProductionStartup => (
+                Duration::from_secs(120),
+                Duration::from_secs(2),
+                Some(Duration::from_secs(1)),
+            ),
+        };
+
+        SituwaitionOpts {
+            timeout,
+            check_interval,
+            check_cooldown,
+            ..SituwaitionOpts::builtin()
+        }
+    }
+}
+
+impl From<Preset> for SituwaitionOpts {
+    fn from(preset: Preset) -> Self {
+        preset.opts()
+    }
+}
+
+fn read() -> RwLockReadGuard<'static, Option<SituwaitionOpts>> {
+    DEFAULT_OPTS.read().unwrap_or_else(|e| e.into_inner())
+}
+
+fn write() -> RwLockWriteGuard<'static, Option<SituwaitionOpts>> {
+    DEFAULT_OPTS.write().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Set the options returned by [`SituwaitionOpts::default()`] for the rest of the process
+///
+/// Only the timings (timeout, check interval and cooldown) become defaults. Options that belong to a single
+/// situwaition (its name, hooks and cancellation token) are dropped, so they aren't shared by every situwaition.
+pub fn set_default_opts(opts: SituwaitionOpts) -> Result<(), WaiterCreationError> {
+    opts.validate()?;
+    *write() = Some(timings(opts));
+    Ok(())
+}
+
+/// Keep only the timings of the given options
+fn timings(opts: SituwaitionOpts) -> SituwaitionOpts {
+    SituwaitionOpts {
+        timeout: opts.timeout,
+        check_interval: opts.check_interval,
+        check_cooldown: opts.check_cooldown,
+        ..SituwaitionOpts::builtin()
+    }
+}
+
+/// Use a [`Preset`] as the default options for the rest of the process
+pub fn set_preset(preset: Preset) {
+    *write() = Some(preset.opts());
+}
+
+/// Return to the built-in default options
+pub fn reset() {
+    *write() = None;
+}
+
+/// The default options currently in effect
+pub fn default_opts() -> SituwaitionOpts {
+    read().clone().unwrap_or_else(SituwaitionOpts::builtin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_presets_are_valid() {
+        for preset in [
+            Preset::FastUnitTest,
+            Preset::IntegrationTest,
+            Preset::ProductionStartup,
+        ] {
+            assert!(
+                preset.opts().validate().is_ok(),
+                "preset {preset:?} is valid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_unit_set_default_opts_validates() {
+        let result = set_default_opts(SituwaitionOpts {
+            timeout: Duration::from_millis(10),
+            check_interval: Duration::from_millis(100),
+            ..SituwaitionOpts::builtin()
+        });
+        assert!(
+            matches!(result, Err(WaiterCreationError::InvalidTimeout(_))),
+            "invalid defaults are rejected"
+        );
+    }
+
+    #[test]
+    fn test_unit_timings_drop_per_wait_state() {
+        let opts = timings(SituwaitionOpts {
+            name: Some("db".into()),
+            check_cooldown: Some(Duration::from_millis(5)),
+            hooks: crate::SituwaitionHooks::default().on_attempt(|_| {}),
+            cancellation: Some(crate::cancel::CancellationToken::new()),
+            ..SituwaitionOpts::builtin()
+        });
+
+        assert_eq!(opts.check_cooldown, Some(Duration::from_millis(5)));
+        assert!(opts.name.is_none(), "name is dropped");
+        assert!(opts.hooks.on_attempt.is_none(), "hooks are dropped");
+        assert!(opts.cancellation.is_none(), "cancellation is dropped");
+    }
+}
@@ -0,0 +1,288 @@
+//! Exporting situwaition events and reports for consumption by CI systems
+//!
+//! - [`JsonLinesSink`] writes one JSON object per line for every situwaition event (and [`WaitReport`]s)
+//! - [`write_junit_testcase`] writes a [`WaitReport`] as a JUnit XML `<testcase>` fragment
+//!
+//! Errors are always exported via their [`Display`][std::fmt::Display] output, so error types
+//! do not need to implement [`Serialize`].
+#![cfg(feature = "serde")]
+
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde::{Serialize, Serializer};
+
+use crate::{report::WaitOutcome, SituwaitionHooks, WaitReport};
+
+/// Serialize a [`Duration`] as (fractional) milliseconds
+pub(crate) fn serialize_millis<S: Serializer>(d: &Duration, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_f64(d.as_secs_f64() * 1_000.0)
+}
+
+/// A single situwaition event, as written by [`JsonLinesSink`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WaitEvent<'a> {
+    /// An attempt was started
+    Attempt {
+        name: Option<&'a str>,
+        attempt: usize,
+        #[serde(rename = "elapsed_ms", serialize_with = "serialize_millis")]
+        elapsed: Duration,
+    },
+
+    /// An attempt failed
+    Failure {
+        name: Option<&'a str>,
+        attempt: usize,
+        #[serde(rename = "elapsed_ms", serialize_with = "serialize_millis")]
+        elapsed: Duration,
+        error: String,
+    },
+
+    /// The situwaition succeeded
+    Success {
+        name: Option<&'a str>,
+        attempts: usize,
+        #[serde(rename = "elapsed_ms", serialize_with = "serialize_millis")]
+        elapsed: Duration,
+    },
+
+    /// The situwaition timed out
+    Timeout {
+        name: Option<&'a str>,
+        attempts: usize,
+        #[serde(rename = "elapsed_ms", serialize_with = "serialize_millis")]
+        elapsed: Duration,
+    },
+
+    /// A full report of a situwaition
+    Report(&'a WaitReport),
+}
+
+/// Writes situwaition events as JSON lines
+///
+/// ```
+/// use situwaition::{export::JsonLinesSink, sync::SyncWaiter, SyncSituwaition};
+///
+/// let sink = JsonLinesSink::new(std::io::stderr());
+/// let result = SyncWaiter::from_fn(|| Ok::<_, std::io::Error>(true))
+///     .with_hooks(sink.hooks(Some("example")))
+///     .exec();
+/// ```
+#[derive(Clone)]
+pub struct JsonLinesSink {
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+}
+
+impl JsonLinesSink {
+    /// Create a sink that writes to the given writer
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        JsonLinesSink {
+            writer: Arc::new(Mutex::new(Box::new(writer))),
+        }
+    }
+
+    /// Write a single event as a line of JSON
+    pub fn write_event(&self, event: &WaitEvent<'_>) -> std::io::Result<()> {
+        let mut line = serde_json::to_vec(event)?;
+        line.push(b'\n');
+
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        writer.write_all(&line)?;
+        writer.flush()
+    }
+
+    /// Write a full report as a line of JSON
+    pub fn write_report(&self, report: &WaitReport) -> std::io::Result<()> {
+        self.write_event(&WaitEvent::Report(report))
+    }
+
+    /// Produce hooks that write every event of a situwaition to this sink.
+    ///
+    /// Since hooks cannot fail, errors that occur while writing are ignored.
+    pub fn hooks(&self, name: Option<&str>) -> SituwaitionHooks {
+        let name = name.map(String::from);
+        let (attempt_sink, attempt_name) = (self.clone(), name.clone());
+        let (failure_sink, failure_name) = (self.clone(), name.clone());
+        let (success_sink, success_name) = (self.clone(), name.clone());
+        let (timeout_sink, timeout_name) = (self.clone(), name);
+
+        SituwaitionHooks::new()
+            .on_attempt(move |info| {
+                let _ = attempt_sink.write_event(&WaitEvent::Attempt {
+                    name: attempt_name.as_deref(),
+                    attempt: info.attempt,
+                    elapsed: info.elapsed,
+                });
+            })
+            .on_failure(move |info, err| {
+                let _ = failure_sink.write_event(&WaitEvent::Failure {
+                    name: failure_name.as_deref(),
+                    attempt: info.attempt,
+                    elapsed: info.elapsed,
+                    error: err.to_string(),
+                });
+            })
+            .on_success(move |info| {
+                let _ = success_sink.write_event(&WaitEvent::Success {
+                    name: success_name.as_deref(),
+                    attempts: info.attempt,
+                    elapsed: info.elapsed,
+                });
+            })
+            .on_timeout(move |info| {
+                let _ = timeout_sink.write_event(&WaitEvent::Timeout {
+                    name: timeout_name.as_deref(),
+                    attempts: info.attempt,
+                    elapsed: info.elapsed,
+                });
+                None
+            })
+    }
+}
+
+/// Escape text for inclusion in XML attributes and text nodes
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Write a [`WaitReport`] as a JUnit XML `<testcase>` element, for inclusion in a `<testsuite>`.
+///
+/// Situwaitions that did not succeed are written with a `<failure>` containing the report's timeline.
+pub fn write_junit_testcase(
+    mut w: impl Write,
+    classname: &str,
+    report: &WaitReport,
+) -> std::io::Result<()> {
+    let name = escape_xml(report.name.as_deref().unwrap_or("<unnamed>"));
+    let classname = escape_xml(classname);
+    let time = report.elapsed.as_secs_f64();
+
+    if report.outcome == WaitOutcome::Succeeded {
+        return writeln!(
+            w,
+            r#"<testcase name="{name}" classname="{classname}" time="{time:.3}"/>"#
+        );
+    }
+
+    let failure_type = match report.outcome {
+        WaitOutcome::CheckTimedOut => "check_timeout",
+        WaitOutcome::TimedOut => "timeout",
+        WaitOutcome::Cancelled => "cancelled",
+        _ => "unfinished",
+    };
+    let message = escape_xml(
+        report
+            .error
+            .as_deref()
+            .unwrap_or("situwaition did not succeed"),
+    );
+    writeln!(
+        w,
+        r#"<testcase name="{name}" classname="{classname}" time="{time:.3}">"#
+    )?;
+    writeln!(
+        w,
+        r#"  <failure message="{message}" type="{failure_type}">{}</failure>"#,
+        escape_xml(&report.to_string())
+    )?;
+    writeln!(w, "</testcase>")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::report::{AttemptOutcome, AttemptRecord};
+
+    use super::*;
+
+    /// Writer that can be inspected after being handed to a sink
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_unit_json_lines_sink_hooks() {
+        let buf = SharedBuf::default();
+        let hooks = JsonLinesSink::new(buf.clone()).hooks(Some("db"));
+        let info = crate::AttemptInfo {
+            attempt: 1,
+            elapsed: Duration::from_millis(5),
+        };
+
+        (hooks.on_attempt.unwrap())(&info);
+        (hooks.on_failure.unwrap())(
+            &info,
+            &std::io::Error::new(std::io::ErrorKind::Other, "not ready"),
+        );
+        assert_eq!((hooks.on_timeout.unwrap())(&info), None);
+
+        let output = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+        let events = output
+            .lines()
+            .map(|l| serde_json::from_str::<serde_json::Value>(l).expect("invalid json line"))
+            .collect::<Vec<_>>();
+        assert_eq!(events.len(), 3, "one line per event");
+        assert_eq!(events[0]["event"], "attempt");
+        assert_eq!(events[0]["name"], "db");
+        assert_eq!(events[0]["elapsed_ms"], 5.0);
+        assert_eq!(events[1]["event"], "failure");
+        assert_eq!(events[1]["error"], "not ready");
+        assert_eq!(events[2]["event"], "timeout");
+    }
+
+    #[test]
+    fn test_unit_junit_testcase() {
+        let report = WaitReport {
+            name: Some("db <primary>".into()),
+            outcome: WaitOutcome::TimedOut,
+            elapsed: Duration::from_millis(1_500),
+            attempts: vec![AttemptRecord {
+                attempt: 1,
+                started_at: Duration::ZERO,
+                duration: Duration::from_millis(10),
+                outcome: AttemptOutcome::Failed,
+                error: Some("refused & closed".into()),
+            }],
+            error: Some("failed repeatedly until the timeout".into()),
+            diagnostics: None,
+            timeout_scale: None,
+        };
+
+        let mut out = Vec::new();
+        write_junit_testcase(&mut out, "startup", &report).expect("failed to write");
+        let xml = String::from_utf8(out).unwrap();
+
+        assert!(
+            xml.starts_with(
+                r#"<testcase name="db &lt;primary&gt;" classname="startup" time="1.500">"#
+            ),
+            "testcase element is written with escaped attributes"
+        );
+        assert!(xml.contains("refused &amp; closed"), "timeline is escaped");
+        assert!(xml.trim_end().ends_with("</testcase>"));
+    }
+}
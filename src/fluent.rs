@@ -0,0 +1,416 @@
+//! Single entry point for situwaitions, regardless of how they are executed
+//!
+//! [`Situwaition`] collects options, which are validated by [`build`][Situwaition::build] before the
+//! situwaition is run with the executor of choice -- [`run_sync`][ReadySituwaition::run_sync], or
+//! (with the corresponding features enabled) `run_tokio`, `run_async_std` and `run_smol`:
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use situwaition::Situwaition;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let result = Situwaition::named("db")
+//!     .timeout(Duration::from_secs(1))
+//!     .interval(Duration::from_millis(50))
+//!     .build()?
+//!     .run_sync(|| Ok::<_, std::io::Error>("ready"));
+//! assert!(matches!(result, Ok("ready")));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Once both the timeout and the interval are set, the situwaition can also be run without calling
+//! `build()`, in which case invalid options are reported as [`SituwaitionError::InvalidOptions`]:
+//!
+//! ```
+//! # use std::time::Duration;
+//! # use situwaition::Situwaition;
+//! let result = Situwaition::new()
+//!     .timeout(Duration::from_secs(1))
+//!     .interval(Duration::from_millis(50))
+//!     .run_sync(|| Ok::<_, std::io::Error>("ready"));
+//! assert!(matches!(result, Ok("ready")));
+//! ```
+//!
+//! The type of the builder tracks which of the timeout, interval and cooldown have been set,
+//! so that none of them can be set twice (the name is given up front, with [`Situwaition::named`]).
+//! Options that are not set are taken from [`SituwaitionOpts::default()`] when the situwaition is built.
+
+use std::{
+    fmt::{Debug, Display},
+    marker::PhantomData,
+    time::Duration,
+};
+
+#[cfg(any(feature = "tokio", feature = "async-std", feature = "smol"))]
+use std::error::Error;
+
+#[cfg(any(feature = "tokio", feature = "async-std", feature = "smol"))]
+use std::future::Future;
+
+#[cfg(any(feature = "tokio", feature = "async-std", feature = "smol"))]
+use crate::runtime::{AsyncRuntime, AsyncWaiter};
+use crate::{
+    cancel::CancellationToken, sync::SyncWaiter, SituwaitionError, SituwaitionHooks,
+    SituwaitionOpts, SituwaitionOptsBuilder, SyncSituwaition, WaiterCreationError,
+};
+
+/// Marks an option of a [`Situwaition`] that has not been set yet
+#[derive(Debug)]
+pub struct Unset;
+
+/// Marks an option of a [`Situwaition`] that has been set
+#[derive(Debug)]
+pub struct Set;
+
+/// Options for a situwaition that has yet to be run.
+///
+/// `T`, `I` and `C` track whether the timeout, the interval and the cooldown have been set ([`Unset`] or [`Set`]),
+/// so setting any of them twice does not compile:
+///
+/// ```compile_fail
+/// # use std::time::Duration;
+/// # use situwaition::Situwaition;
+/// Situwaition::new()
+///     .timeout(Duration::from_secs(1))
+///     .timeout(Duration::from_secs(2));
+/// ```
+///
+/// ```compile_fail
+/// # use std::time::Duration;
+/// # use situwaition::Situwaition;
+/// Situwaition::new()
+///     .cooldown(Duration::from_millis(10))
+///     .cooldown(Duration::from_millis(20));
+/// ```
+pub struct Situwaition<T = Unset, I = Unset, C = Unset> {
+    opts: SituwaitionOptsBuilder,
+    state: PhantomData<(T, I, C)>,
+}
+
+impl Situwaition {
+    /// Start a situwaition with the default options
+    pub fn new() -> Self {
+        Situwaition {
+            opts: SituwaitionOptsBuilder::default(),
+            state: PhantomData,
+        }
+    }
+
+    /// Start a named situwaition (see [`SituwaitionOpts::name`]) with the default options
+    pub fn named(name: impl Into<String>) -> Self {
+        let mut situwaition = Situwaition::new();
+        situwaition.opts.name(name);
+        situwaition
+    }
+}
+
+impl Default for Situwaition {
+    fn default() -> Self {
+        Situwaition::new()
+    }
+}
+
+impl<T, I, C> Situwaition<T, I, C> {
+    /// Carry the options over to a builder with a different typestate
+    fn transition<T2, I2, C2>(self) -> Situwaition<T2, I2, C2> {
+        Situwaition {
+            opts: self.opts,
+            state: PhantomData,
+        }
+    }
+}
+
+impl<I, C> Situwaition<Unset, I, C> {
+    /// Set the maximum time to wait (see [`SituwaitionOpts::timeout`])
+    pub fn timeout(mut self, timeout: Duration) -> Situwaition<Set, I, C> {
+        self.opts.timeout(timeout);
+        self.transition()
+    }
+}
+
+impl<T, C> Situwaition<T, Unset, C> {
+    /// Set how often to check (see [`SituwaitionOpts::check_interval`])
+    pub fn interval(mut self, check_interval: Duration) -> Situwaition<T, Set, C> {
+        self.opts.check_interval(check_interval);
+        self.transition()
+    }
+}
+
+impl<T, I> Situwaition<T, I, Unset> {
+    /// Set the time to wait after each check (see [`SituwaitionOpts::check_cooldown`])
+    pub fn cooldown(mut self, check_cooldown: Duration) -> Situwaition<T, I, Set> {
+        self.opts.check_cooldown(Some(check_cooldown));
+        self.transition()
+    }
+}
+
+impl<T, I, C> Situwaition<T, I, C> {
+    /// Set the lifecycle hooks that will be run during the situwaition, replacing any that were set before
+    pub fn hooks(mut self, hooks: SituwaitionHooks) -> Self {
+        self.opts.hooks(hooks);
+        self
+    }
+
+    /// Stop the situwaition when the given token is cancelled (see [`crate::cancel`]),
+    /// replacing any token that was set before
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.opts.cancellation(token);
+        self
+    }
+
+    /// Validate the options, producing a situwaition that is ready to run
+    pub fn build(self) -> Result<ReadySituwaition, WaiterCreationError> {
+        Ok(ReadySituwaition {
+            opts: self.opts.build()?,
+        })
+    }
+}
+
+impl<C> Situwaition<Set, Set, C> {
+    /// Validate the options and run the situwaition synchronously (see [`ReadySituwaition::run_sync`])
+    pub fn run_sync<R, E, F>(self, check_fn: F) -> Result<R, SituwaitionError<E>>
+    where
+        R: Send + 'static,
+        E: Debug + Display + Send + 'static,
+        F: Fn() -> Result<R, E> + Send + Sync + 'static,
+    {
+        self.build()
+            .map_err(SituwaitionError::InvalidOptions)?
+            .run_sync(check_fn)
+    }
+
+    /// Validate the options and run the situwaition on the tokio runtime (see [`ReadySituwaition::run_tokio`])
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub async fn run_tokio<F, A, R, E>(self, factory: A) -> Result<R, SituwaitionError<E>>
+    where
+        F: Future<Output = Result<R, E>> + Send,
+        A: Fn() -> F + Send,
+        R: Send + Sync,
+        E: Error + Send + Sync,
+    {
+        self.build()
+            .map_err(SituwaitionError::InvalidOptions)?
+            .run_tokio(factory)
+            .await
+    }
+
+    /// Validate the options and run the situwaition on the async-std runtime (see [`ReadySituwaition::run_async_std`])
+    #[cfg(feature = "async-std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async-std")))]
+    pub async fn run_async_std<F, A, R, E>(self, factory: A) -> Result<R, SituwaitionError<E>>
+    where
+        F: Future<Output = Result<R, E>> + Send,
+        A: Fn() -> F + Send,
+        R: Send + Sync,
+        E: Error + Send + Sync,
+    {
+        self.build()
+            .map_err(SituwaitionError::InvalidOptions)?
+            .run_async_std(factory)
+            .await
+    }
+
+    /// Validate the options and run the situwaition on the smol runtime (see [`ReadySituwaition::run_smol`])
+    #[cfg(feature = "smol")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "smol")))]
+    pub async fn run_smol<F, A, R, E>(self, factory: A) -> Result<R, SituwaitionError<E>>
+    where
+        F: Future<Output = Result<R, E>> + Send,
+        A: Fn() -> F + Send,
+        R: Send + Sync,
+        E: Error + Send + Sync,
+    {
+        self.build()
+            .map_err(SituwaitionError::InvalidOptions)?
+            .run_smol(factory)
+            .await
+    }
+}
+
+/// A situwaition with validated options, produced by [`Situwaition::build`]
+#[derive(Debug)]
+pub struct ReadySituwaition {
+    opts: SituwaitionOpts,
+}
+
+impl ReadySituwaition {
+    /// The options the situwaition will run with
+    pub fn options(&self) -> &SituwaitionOpts {
+        &self.opts
+    }
+
+    /// Run the situwaition synchronously (see [`SyncWaiter`])
+    pub fn run_sync<R, E, F>(self, check_fn: F) -> Result<R, SituwaitionError<E>>
+    where
+        R: Send + 'static,
+        E: Debug + Display + Send + 'static,
+        F: Fn() -> Result<R, E> + Send + Sync + 'static,
+    {
+        SyncWaiter::with_opts(check_fn, self.opts).exec()
+    }
+
+    /// Run the situwaition on the tokio runtime (see [`AsyncWaiter`])
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub async fn run_tokio<F, A, R, E>(self, factory: A) -> Result<R, SituwaitionError<E>>
+    where
+        F: Future<Output = Result<R, E>> + Send,
+        A: Fn() -> F + Send,
+        R: Send + Sync,
+        E: Error + Send + Sync,
+    {
+        AsyncWaiter::with_opts(factory, self.opts)
+            .exec_on(AsyncRuntime::Tokio)
+            .await
+    }
+
+    /// Run the situwaition on the async-std runtime (see [`AsyncWaiter`])
+    #[cfg(feature = "async-std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async-std")))]
+    pub async fn run_async_std<F, A, R, E>(self, factory: A) -> Result<R, SituwaitionError<E>>
+    where
+        F: Future<Output = Result<R, E>> + Send,
+        A: Fn() -> F + Send,
+        R: Send + Sync,
+        E: Error + Send + Sync,
+    {
+        AsyncWaiter::with_opts(factory, self.opts)
+            .exec_on(AsyncRuntime::AsyncStd)
+            .await
+    }
+
+    /// Run the situwaition on the smol runtime (see [`AsyncWaiter`])
+    #[cfg(feature = "smol")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "smol")))]
+    pub async fn run_smol<F, A, R, E>(self, factory: A) -> Result<R, SituwaitionError<E>>
+    where
+        F: Future<Output = Result<R, E>> + Send,
+        A: Fn() -> F + Send,
+        R: Send + Sync,
+        E: Error + Send + Sync,
+    {
+        AsyncWaiter::with_opts(factory, self.opts)
+            .exec_on(AsyncRuntime::Smol)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Error, ErrorKind};
+
+    use super::*;
+
+    #[test]
+    fn test_unit_situwaition_run_sync() {
+        let result = Situwaition::new()
+            .interval(Duration::from_millis(10))
+            .timeout(Duration::from_millis(100))
+            .build()
+            .expect("valid options")
+            .run_sync(|| Err::<(), _>(Error::new(ErrorKind::Other, "not ready")));
+        assert!(
+            matches!(result, Err(SituwaitionError::TimeoutError(..))),
+            "failing checks time out"
+        );
+    }
+
+    #[test]
+    fn test_unit_situwaition_invalid_opts() {
+        let result = Situwaition::new().timeout(Duration::ZERO).build();
+        assert!(
+            matches!(result, Err(WaiterCreationError::InvalidTimeout(_))),
+            "invalid options are reported before running"
+        );
+
+        let result = Situwaition::new()
+            .timeout(Duration::from_millis(100))
+            .interval(Duration::from_millis(10))
+            .cooldown(Duration::from_millis(200))
+            .build();
+        assert!(
+            matches!(result, Err(WaiterCreationError::InvalidCooldown(_))),
+            "invalid combinations are reported before running"
+        );
+    }
+
+    #[test]
+    fn test_unit_situwaition_run_sync_without_build() {
+        let result = Situwaition::new()
+            .timeout(Duration::from_millis(100))
+            .interval(Duration::from_millis(10))
+            .run_sync(|| Ok::<_, Error>(5));
+        assert!(matches!(result, Ok(5)), "fully specified situwaitions run");
+
+        let result = Situwaition::new()
+            .timeout(Duration::from_millis(100))
+            .interval(Duration::from_millis(200))
+            .run_sync(|| Ok::<_, Error>(5));
+        assert!(
+            matches!(
+                result,
+                Err(SituwaitionError::InvalidOptions(
+                    WaiterCreationError::InvalidInterval(_)
+                ))
+            ),
+            "invalid options are reported in the result"
+        );
+    }
+
+    #[test]
+    fn test_unit_situwaition_build() {
+        let situwaition = Situwaition::named("db")
+            .cooldown(Duration::from_millis(5))
+            .interval(Duration::from_millis(10))
+            .timeout(Duration::from_millis(100))
+            .build()
+            .expect("valid options");
+
+        let opts = situwaition.options();
+        assert_eq!(opts.name.as_deref(), Some("db"));
+        assert_eq!(opts.timeout, Duration::from_millis(100));
+        assert_eq!(opts.check_interval, Duration::from_millis(10));
+        assert_eq!(opts.check_cooldown, Some(Duration::from_millis(5)));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_unit_situwaition_run_tokio() {
+        let result = Situwaition::new()
+            .timeout(Duration::from_millis(500))
+            .interval(Duration::from_millis(10))
+            .run_tokio(|| async { Ok::<_, Error>(5) })
+            .await;
+        assert!(matches!(result, Ok(5)), "tokio run succeeds");
+    }
+
+    #[cfg(feature = "async-std")]
+    #[async_std::test]
+    async fn test_unit_situwaition_run_async_std() {
+        let result = Situwaition::new()
+            .timeout(Duration::from_millis(500))
+            .interval(Duration::from_millis(10))
+            .build()
+            .expect("valid options")
+            .run_async_std(|| async { Ok::<_, Error>(5) })
+            .await;
+        assert!(matches!(result, Ok(5)), "async-std run succeeds");
+    }
+
+    #[cfg(feature = "smol")]
+    #[test]
+    fn test_unit_situwaition_run_smol() {
+        let result = smol::block_on(
+            Situwaition::new()
+                .timeout(Duration::from_millis(500))
+                .interval(Duration::from_millis(10))
+                .build()
+                .expect("valid options")
+                .run_smol(|| async { Ok::<_, Error>(5) }),
+        );
+        assert!(matches!(result, Ok(5)), "smol run succeeds");
+    }
+}
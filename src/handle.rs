@@ -0,0 +1,266 @@
+//! Handles to situwaitions that run in the background
+//!
+//! Rather than blocking the caller like [`SyncSituwaition::exec`][crate::SyncSituwaition::exec],
+//! a situwaition can be spawned (ex. with [`SyncWaiter::spawn`][crate::sync::SyncWaiter::spawn]),
+//! producing a handle that can be checked without blocking, waited on with a timeout, cancelled,
+//! and asked for live [`Progress`]:
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use situwaition::{sync::SyncWaiter, SituwaitionError};
+//!
+//! let mut handle = SyncWaiter::from_fn(|| Err::<(), _>(std::io::Error::new(std::io::ErrorKind::Other, "not ready"))).spawn();
+//!
+//! // ... do other work, then check in
+//! assert!(handle.try_result().is_none(), "still waiting");
+//! println!("{} attempt(s) so far", handle.progress().attempts);
+//!
+//! handle.cancel();
+//! assert!(matches!(handle.join(), Err(SituwaitionError::Cancelled)));
+//! ```
+//!
+//! With the `tokio` feature, [`AsyncWaiter::spawn`][crate::runtime::AsyncWaiter::spawn] runs the
+//! situwaition as a tokio task instead, producing an [`AsyncWaitHandle`].
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::Receiver,
+        Arc, Mutex,
+    },
+    thread::Thread,
+    time::Duration,
+};
+
+use crate::SituwaitionError;
+
+/// Live progress of a spawned situwaition
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Progress {
+    /// The number of attempts that have been started so far
+    pub attempts: usize,
+
+    /// The error produced by the most recent failed attempt
+    pub last_error: Option<String>,
+}
+
+/// Slot that a spawned task stores its result in
+#[cfg(feature = "tokio")]
+pub(crate) type ResultSlot<R, E> = Arc<Mutex<Option<Result<R, SituwaitionError<E>>>>>;
+
+/// Read the progress shared with a spawned situwaition
+fn read_progress(progress: &Mutex<Progress>) -> Progress {
+    progress.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Handle to a situwaition running on a background thread, created with
+/// [`SyncWaiter::spawn`][crate::sync::SyncWaiter::spawn]
+///
+/// The result can only be retrieved once -- after [`WaitHandle::try_result`] or [`WaitHandle::join_timeout`]
+/// have produced it, both return `None` (and [`WaitHandle::join`] fails with an unexpected error).
+pub struct WaitHandle<R, E> {
+    result: Receiver<Result<R, SituwaitionError<E>>>,
+    progress: Arc<Mutex<Progress>>,
+    cancelled: Arc<AtomicBool>,
+
+    /// The thread waiting on the situwaition, which is unparked when it is cancelled
+    thread: Thread,
+}
+
+impl<R, E> WaitHandle<R, E> {
+    pub(crate) fn new(
+        result: Receiver<Result<R, SituwaitionError<E>>>,
+        progress: Arc<Mutex<Progress>>,
+        cancelled: Arc<AtomicBool>,
+        thread: Thread,
+    ) -> Self {
+        WaitHandle {
+            result,
+            progress,
+            cancelled,
+            thread,
+        }
+    }
+
+    /// Attempts made so far, and the most recent error
+    pub fn progress(&self) -> Progress {
+        read_progress(&self.progress)
+    }
+
+    /// Retrieve the result if the situwaition has finished, without blocking
+    pub fn try_result(&mut self) -> Option<Result<R, SituwaitionError<E>>> {
+        self.result.try_recv().ok()
+    }
+
+    /// Wait up to `timeout` for the situwaition to finish, retrieving the result if it did
+    pub fn join_timeout(&mut self, timeout: Duration) -> Option<Result<R, SituwaitionError<E>>> {
+        self.result.recv_timeout(timeout).ok()
+    }
+
+    /// Wait for the situwaition to finish
+    pub fn join(self) -> Result<R, SituwaitionError<E>> {
+        self.result.recv().unwrap_or_else(|_| {
+            Err(SituwaitionError::UnexpectedError(
+                "result was already retrieved".into(),
+            ))
+        })
+    }
+
+    /// Stop the situwaition, which then finishes with [`SituwaitionError::Cancelled`]
+    /// (unless it finished in the meantime).
+    ///
+    /// Cancellation takes effect right away. A check that is already running is not
+    /// interrupted, but no further attempts are made.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.thread.unpark();
+    }
+}
+
+/// Handle to a situwaition running as a tokio task, created with
+/// [`AsyncWaiter::spawn`][crate::runtime::AsyncWaiter::spawn]
+///
+/// Like [`WaitHandle`], the result can only be retrieved once.
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub struct AsyncWaitHandle<R, E> {
+    /// The task, until it has been joined
+    task: Option<tokio::task::JoinHandle<()>>,
+    result: ResultSlot<R, E>,
+    progress: Arc<Mutex<Progress>>,
+}
+
+#[cfg(feature = "tokio")]
+impl<R, E> AsyncWaitHandle<R, E> {
+    pub(crate) fn new(
+        task: tokio::task::JoinHandle<()>,
+        result: ResultSlot<R, E>,
+        progress: Arc<Mutex<Progress>>,
+    ) -> Self {
+        AsyncWaitHandle {
+            task: Some(task),
+            result,
+            progress,
+        }
+    }
+
+    /// Attempts made so far, and the most recent error
+    pub fn progress(&self) -> Progress {
+        read_progress(&self.progress)
+    }
+
+    /// Take the result stored by the task, or [`SituwaitionError::Cancelled`] if the task ended without one
+    fn take_result(&mut self) -> Option<Result<R, SituwaitionError<E>>> {
+        self.task.take()?;
+        let result = self.result.lock().unwrap_or_else(|e| e.into_inner()).take();
+        Some(result.unwrap_or(Err(SituwaitionError::Cancelled)))
+    }
+
+    /// Retrieve the result if the situwaition has finished, without waiting
+    pub fn try_result(&mut self) -> Option<Result<R, SituwaitionError<E>>> {
+        match &self.task {
+            Some(task) if task.is_finished() => self.take_result(),
+            _ => None,
+        }
+    }
+
+    /// Wait up to `timeout` for the situwaition to finish, retrieving the result if it did
+    pub async fn join_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Option<Result<R, SituwaitionError<E>>> {
+        let task = self.task.as_mut()?;
+        // The task either completed or was aborted, and the result slot tells which
+        let _ = tokio::time::timeout(timeout, task).await.ok()?;
+        self.take_result()
+    }
+
+    /// Wait for the situwaition to finish
+    pub async fn join(mut self) -> Result<R, SituwaitionError<E>> {
+        if let Some(task) = self.task.as_mut() {
+            let _ = task.await;
+        }
+        self.take_result().unwrap_or_else(|| {
+            Err(SituwaitionError::UnexpectedError(
+                "result was already retrieved".into(),
+            ))
+        })
+    }
+
+    /// Stop the situwaition by aborting its task, after which it finishes with [`SituwaitionError::Cancelled`]
+    /// (unless it finished in the meantime).
+    ///
+    /// The check that is running (if any) is dropped at its next `.await`.
+    pub fn cancel(&self) {
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+    }
+}
+
+#[cfg(all(
+    test,
+    not(any(feature = "async-std", feature = "tokio", feature = "smol"))
+))]
+mod tests {
+    use std::{
+        io::ErrorKind,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use crate::{sync::SyncWaiter, SituwaitionError};
+
+    use super::*;
+
+    #[test]
+    fn test_unit_sync_spawn_join() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let shared_count = count.clone();
+        let mut handle =
+            SyncWaiter::builder(move || match shared_count.fetch_add(1, Ordering::SeqCst) {
+                3 => Ok("ready"),
+                _ => Err(std::io::Error::new(ErrorKind::Other, "not ready")),
+            })
+            .check_interval(Duration::from_millis(20))
+            .check_cooldown(Duration::from_millis(20))
+            .build()
+            .expect("failed to create")
+            .spawn();
+
+        assert!(
+            handle.join_timeout(Duration::from_millis(1)).is_none(),
+            "result is not ready right away"
+        );
+        assert!(
+            matches!(handle.join(), Ok("ready")),
+            "joined the successful result"
+        );
+        assert_eq!(count.load(Ordering::SeqCst), 4, "check ran until it passed");
+    }
+
+    #[test]
+    fn test_unit_sync_spawn_progress_and_cancel() {
+        let mut handle = SyncWaiter::with_check_interval(
+            || Err::<(), _>(std::io::Error::new(ErrorKind::Other, "not ready")),
+            Duration::from_millis(10),
+        )
+        .expect("failed to create")
+        .spawn();
+
+        std::thread::sleep(Duration::from_millis(100));
+        let progress = handle.progress();
+        assert!(progress.attempts > 1, "attempts are reported while waiting");
+        assert_eq!(progress.last_error.as_deref(), Some("not ready"));
+        assert!(handle.try_result().is_none(), "still waiting");
+
+        handle.cancel();
+        assert!(
+            matches!(
+                handle.join_timeout(Duration::from_secs(1)),
+                Some(Err(SituwaitionError::Cancelled))
+            ),
+            "cancelled situwaition finishes promptly"
+        );
+    }
+}
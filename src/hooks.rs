@@ -0,0 +1,111 @@
+//! Lifecycle hooks that are run while a situwaition is being waited on
+//!
+//! Hooks are registered on [`SituwaitionOpts`][crate::SituwaitionOpts] (see [`SituwaitionHooks`]),
+//! and are fired by every executor (sync, tokio and async-std).
+
+use std::{error::Error, fmt, sync::Arc, time::Duration};
+
+/// Information about a single attempt, passed to hooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttemptInfo {
+    /// The number of the attempt (the first attempt is 1)
+    pub attempt: usize,
+
+    /// Time elapsed since the situwaition started
+    pub elapsed: Duration,
+}
+
+/// Hook that is run with information about an attempt
+pub type AttemptHook = Arc<dyn Fn(&AttemptInfo) + Send + Sync>;
+
+/// Hook that is run when an attempt fails
+pub type FailureHook = Arc<dyn Fn(&AttemptInfo, &dyn Error) + Send + Sync>;
+
+/// Hook that is run when the situwaition times out, which may return diagnostic text
+pub type TimeoutHook = Arc<dyn Fn(&AttemptInfo) -> Option<String> + Send + Sync>;
+
+/// Callbacks that are run at different points of a situwaition's lifecycle
+///
+/// ```
+/// use situwaition::SituwaitionHooks;
+///
+/// let hooks = SituwaitionHooks::new()
+///     .on_failure(|info, err| eprintln!("attempt #{} failed: {err}", info.attempt))
+///     .on_timeout(|info| Some(format!("gave up after {} attempts", info.attempt)));
+/// ```
+#[derive(Clone, Default)]
+pub struct SituwaitionHooks {
+    /// Run before every attempt
+    pub on_attempt: Option<AttemptHook>,
+
+    /// Run after every failed attempt
+    pub on_failure: Option<FailureHook>,
+
+    /// Run once, after the successful attempt
+    pub on_success: Option<AttemptHook>,
+
+    /// Run once when the situwaition is cancelled, or dropped before it finished (ex. an async situwaition
+    /// whose future was dropped). When dropped, the elapsed time is as of the most recent attempt.
+    pub on_cancel: Option<AttemptHook>,
+
+    /// Run once when the situwaition times out (whether from repeated failure or a check that ran too long).
+    ///
+    /// Text returned from this hook is attached to the returned error (see [`SituwaitionError::diagnostics`][crate::SituwaitionError::diagnostics]),
+    /// and recorded in the [`WaitReport`][crate::WaitReport::diagnostics].
+    pub on_timeout: Option<TimeoutHook>,
+}
+
+impl SituwaitionHooks {
+    /// Create an empty set of hooks
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the hook that is run before every attempt
+    pub fn on_attempt(mut self, hook: impl Fn(&AttemptInfo) + Send + Sync + 'static) -> Self {
+        self.on_attempt = Some(Arc::new(hook));
+        self
+    }
+
+    /// Set the hook that is run after every failed attempt
+    pub fn on_failure(
+        mut self,
+        hook: impl Fn(&AttemptInfo, &dyn Error) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_failure = Some(Arc::new(hook));
+        self
+    }
+
+    /// Set the hook that is run after the successful attempt
+    pub fn on_success(mut self, hook: impl Fn(&AttemptInfo) + Send + Sync + 'static) -> Self {
+        self.on_success = Some(Arc::new(hook));
+        self
+    }
+
+    /// Set the hook that is run when the situwaition is cancelled (or dropped before it finished)
+    pub fn on_cancel(mut self, hook: impl Fn(&AttemptInfo) + Send + Sync + 'static) -> Self {
+        self.on_cancel = Some(Arc::new(hook));
+        self
+    }
+
+    /// Set the hook that is run when the situwaition times out
+    pub fn on_timeout(
+        mut self,
+        hook: impl Fn(&AttemptInfo) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.on_timeout = Some(Arc::new(hook));
+        self
+    }
+}
+
+impl fmt::Debug for SituwaitionHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SituwaitionHooks")
+            .field("on_attempt", &self.on_attempt.is_some())
+            .field("on_failure", &self.on_failure.is_some())
+            .field("on_success", &self.on_success.is_some())
+            .field("on_cancel", &self.on_cancel.is_some())
+            .field("on_timeout", &self.on_timeout.is_some())
+            .finish()
+    }
+}
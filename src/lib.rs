@@ -7,22 +7,31 @@
 //! Situwaition can be used in contexts with or without async runtimes, and generally does what you'd expect (tm):
 //!
 //! ```
+//! use std::sync::{
+//!     atomic::{AtomicUsize, Ordering},
+//!     Arc,
+//! };
+//!
 //! use situwaition::wait_for;
 //!
-//! fn main() -> Result<(), Box<dyn Error>> {
-//!     let value = 0;
+//! fn main() -> Result<(), Box<dyn std::error::Error>> {
+//!     let value = Arc::new(AtomicUsize::new(0));
+//!     let shared_value = value.clone();
 //!
-//!     let result: Result<&str> = wait_for(|| match value == 5 {
+//!     let result = wait_for(move || match shared_value.load(Ordering::SeqCst) == 5 {
 //!         true => Ok("done!"),
 //!         false => {
-//!             value += 1; // NOTE: incrementing like this only works in a single threaded context!
-//!             Err("not yet")
+//!             shared_value.fetch_add(1, Ordering::SeqCst);
+//!             Err(std::io::Error::new(std::io::ErrorKind::Other, "not yet"))
 //!         },
-//!     });
+//!     })?;
+//!
+//!     assert_eq!(result, "done!");
+//!     Ok(())
 //! }
 //! ```
 //!
-//! The example above demonstrates the synchronous usage, but `tokio` and `async-std` and corresponding modules are available as well.
+//! The example above demonstrates the synchronous usage, but `tokio`, `async-std` and `smol` (and corresponding modules) are available as well.
 #![cfg_attr(all(doc, CHANNEL_NIGHTLY), feature(doc_cfg))]
 
 use std::{result::Result, time::Duration};
@@ -30,63 +39,264 @@ use std::{result::Result, time::Duration};
 use derive_builder::Builder;
 use thiserror::Error;
 
-#[cfg(any(feature = "tokio", feature = "async-std"))]
-#[cfg_attr(docsrs, doc(cfg(any(feature = "async-std", feature = "tokio"))))]
+#[cfg(any(feature = "tokio", feature = "async-std", feature = "smol"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "async-std", feature = "tokio", feature = "smol")))
+)]
 use async_trait::async_trait;
 
+use cancel::CancellationToken;
+
+pub mod builder;
+pub mod cancel;
+pub mod clock;
+#[cfg(any(feature = "env", feature = "serde"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "env", feature = "serde"))))]
+pub mod config;
+pub mod defaults;
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub mod export;
+pub mod fluent;
+pub mod handle;
+pub mod hooks;
+mod observer;
+pub mod registry;
+pub mod report;
 pub mod runtime;
+pub mod scale;
 pub mod sync;
 
 const DEFAULT_SITUWAITION_TIMEOUT_MS: u64 = 3_000;
 const DEFAULT_SITUWAITION_CHECK_INTERVAL_MS: u64 = 250;
 
+/// The `no_std` scheduling engine that the sync and async waiters are built on
+pub use situwaition_core as schedule;
+
+pub use fluent::{ReadySituwaition, Situwaition};
+pub use handle::WaitHandle;
+pub use hooks::{AttemptInfo, SituwaitionHooks};
+pub use report::WaitReport;
 pub use sync::wait_for;
 
 /// The type of error that is thrown when
+///
+/// More variants may be added in the future, so matches on it need a wildcard arm.
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum SituwaitionError<E> {
     /// Timeout from repeated failure
-    #[error("failed repeatedly until the timeout: {0}")]
-    TimeoutError(E),
+    #[error("failed repeatedly until the timeout: {0}{1}")]
+    TimeoutError(E, TimeoutDetails),
 
-    #[error("check fn run exceeded the timeout")]
-    CheckTimeoutError,
+    #[error("check fn run exceeded the timeout{0}")]
+    CheckTimeoutError(TimeoutDetails),
 
     /// A single conditoin failure
     #[error("condition check failed: {0}")]
     ConditionFailed(E),
 
+    /// The situwaition was cancelled before it could finish (ex. with [`WaitHandle::cancel`][handle::WaitHandle::cancel]
+    /// or a [`CancellationToken`])
+    #[error("situwaition was cancelled")]
+    Cancelled,
+
     #[error("unexpected error: {0}")]
     UnexpectedError(String),
+
+    /// The options were invalid, so the situwaition was never started (see [`Situwaition::run_sync`])
+    #[error("invalid situwaition options: {0}")]
+    InvalidOptions(WaiterCreationError),
+}
+
+impl<E> SituwaitionError<E> {
+    /// Details of the timeout, if the situwaition timed out
+    pub fn timeout_details(&self) -> Option<&TimeoutDetails> {
+        match self {
+            SituwaitionError::TimeoutError(_, details)
+            | SituwaitionError::CheckTimeoutError(details) => Some(details),
+            _ => None,
+        }
+    }
+
+    /// Diagnostics attached to a timeout by the `on_timeout` hook (see [`SituwaitionHooks`]), if any
+    pub fn diagnostics(&self) -> Option<&str> {
+        self.timeout_details()?.diagnostics.as_deref()
+    }
+
+    /// The timeout scale factor (see [`scale`]) a timed out situwaition ran with, if scaling was in effect
+    pub fn timeout_scale(&self) -> Option<f64> {
+        self.timeout_details()?.timeout_scale
+    }
+}
+
+/// Details of a timeout, carried by [`SituwaitionError::TimeoutError`] and [`SituwaitionError::CheckTimeoutError`]
+///
+/// More fields may be added in the future, so use [`TimeoutDetails::default`] to create one.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct TimeoutDetails {
+    /// Text produced by the `on_timeout` hook (see [`SituwaitionHooks`]), if any
+    pub diagnostics: Option<String>,
+
+    /// The timeout scale factor (see [`scale`]) that was in effect when the situwaition started,
+    /// if scaling was in effect
+    pub timeout_scale: Option<f64>,
+}
+
+/// Shown after the timeout error's own message
+impl std::fmt::Display for TimeoutDetails {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(factor) = self.timeout_scale {
+            write!(f, "{}", scale::describe(factor))?;
+        }
+        if let Some(d) = &self.diagnostics {
+            write!(f, "\n{d}")?;
+        }
+        Ok(())
+    }
 }
 
 /// Options for a given situwaition
 #[allow(dead_code)]
 #[derive(Debug, Clone, Builder)]
+#[builder(
+    default,
+    build_fn(validate = "Self::validate", error = "WaiterCreationError")
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct SituwaitionOpts {
+    /// An optional name for the situwaition, used when reporting on it
+    /// (ex. in tracing spans, metrics labels and the [`registry`])
+    #[builder(default, setter(into, strip_option))]
+    pub name: Option<String>,
+
     /// The maximum time to wait for a situwaition
+    #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
     pub timeout: Duration,
 
-    /// How often to check for a passing condition.
-    /// Note that in the synchronous case, this determines how quickly
-    /// you can return *before* a check actually completes (i.e. timing in 100ms when check_fn takes 500ms)
+    /// How often to check for a passing condition: the time to wait between checks (after the cooldown, if any).
+    /// The wait is cut short at the timeout, so that timeouts are reported right away.
+    #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
     pub check_interval: Duration,
 
     /// Time to wait after a check has been performed.
     /// Use this to avoid running resource-intensive checks too frequently
+    #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
     pub check_cooldown: Option<Duration>,
+
+    /// Callbacks to run during the lifecycle of the situwaition
+    #[builder(default)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub hooks: SituwaitionHooks,
+
+    /// Token that stops the situwaition when cancelled (see [`cancel`])
+    #[builder(default, setter(strip_option))]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub cancellation: Option<CancellationToken>,
 }
 
+/// The process-wide default options (see [`defaults`])
 impl Default for SituwaitionOpts {
     fn default() -> Self {
+        defaults::default_opts()
+    }
+}
+
+impl SituwaitionOpts {
+    /// The built-in default options, ignoring any process-wide overrides (see [`defaults`])
+    pub fn builtin() -> Self {
         SituwaitionOpts {
+            name: None,
             timeout: Duration::from_millis(DEFAULT_SITUWAITION_TIMEOUT_MS),
             check_interval: Duration::from_millis(DEFAULT_SITUWAITION_CHECK_INTERVAL_MS),
             check_cooldown: None,
+            hooks: SituwaitionHooks::default(),
+            cancellation: None,
+        }
+    }
+
+    /// Check that the options are usable together.
+    ///
+    /// This is useful for options that were not built via waiter constructors like
+    /// [`SyncWaiter::with_timeout`][crate::sync::SyncWaiter::with_timeout] (ex. deserialized options).
+    pub fn validate(&self) -> Result<(), WaiterCreationError> {
+        validate_durations(self.timeout, self.check_interval, self.check_cooldown, true)
+    }
+
+    /// The scheduling policy (see [`schedule`]) that the options describe
+    pub fn policy(&self) -> schedule::Policy {
+        schedule::Policy {
+            timeout: self.timeout,
+            check_interval: self.check_interval,
+            check_cooldown: self.check_cooldown,
         }
     }
 }
 
+impl SituwaitionOptsBuilder {
+    /// Validate the options that were set, along with the defaults for those that were not.
+    ///
+    /// When the check interval does not fit in the timeout, the error is attributed to
+    /// whichever was set explicitly (the check interval, if both were).
+    fn validate(&self) -> Result<(), WaiterCreationError> {
+        let defaults = SituwaitionOpts::default();
+        validate_durations(
+            self.timeout.unwrap_or(defaults.timeout),
+            self.check_interval.unwrap_or(defaults.check_interval),
+            self.check_cooldown.unwrap_or(defaults.check_cooldown),
+            self.timeout.is_some() && self.check_interval.is_none(),
+        )
+    }
+}
+
+/// Check that durations are usable together, optionally blaming the timeout
+/// (rather than the check interval) when the check interval is longer than the timeout
+fn validate_durations(
+    timeout: Duration,
+    check_interval: Duration,
+    check_cooldown: Option<Duration>,
+    blame_timeout: bool,
+) -> Result<(), WaiterCreationError> {
+    if timeout.is_zero() {
+        return Err(WaiterCreationError::InvalidTimeout(
+            "timeout must be greater than zero".into(),
+        ));
+    }
+
+    if check_interval.is_zero() {
+        return Err(WaiterCreationError::InvalidInterval(
+            "check interval must be greater than zero".into(),
+        ));
+    }
+
+    if check_interval > timeout {
+        let (timeout, check_interval) = (timeout.as_millis(), check_interval.as_millis());
+        return Err(match blame_timeout {
+            true => WaiterCreationError::InvalidTimeout(format!(
+                "timeout ({timeout}ms) is shorter than the check interval ({check_interval}ms)"
+            )),
+            false => WaiterCreationError::InvalidInterval(format!(
+                "check interval ({check_interval}ms) is longer than the timeout ({timeout}ms)"
+            )),
+        });
+    }
+
+    match check_cooldown {
+        Some(cooldown) if cooldown.is_zero() => Err(WaiterCreationError::InvalidCooldown(
+            "check cooldown must be greater than zero (leave it unset to disable it)".into(),
+        )),
+        Some(cooldown) if cooldown > timeout => Err(WaiterCreationError::InvalidCooldown(format!(
+            "check cooldown ({}ms) is longer than the timeout ({}ms)",
+            cooldown.as_millis(),
+            timeout.as_millis()
+        ))),
+        _ => Ok(()),
+    }
+}
+
 /// The basic requirements of any situwaition
 pub trait SituwaitionBase {
     type Result;
@@ -107,10 +317,33 @@ pub trait SyncSituwaition: SituwaitionBase {
     /// Execute the situwaition, and wait until it resolves
     /// or fails with a timeout
     fn exec(&mut self) -> Result<Self::Result, SituwaitionError<Self::Error>>;
+
+    /// Execute the situwaition like [`SyncSituwaition::exec`], additionally
+    /// producing a [`WaitReport`] of the attempts that were made
+    ///
+    /// The default implementation only reports how the situwaition ended, without its attempts.
+    fn exec_with_report(
+        &mut self,
+    ) -> (
+        Result<Self::Result, SituwaitionError<Self::Error>>,
+        WaitReport,
+    )
+    where
+        Self::Error: std::fmt::Display,
+    {
+        let started = std::time::Instant::now();
+        let result = self.exec();
+        let report =
+            WaitReport::from_result(self.options().name.clone(), &result, started.elapsed());
+        (result, report)
+    }
 }
 
 /// This trait represents a "situwaition" that can be a"waited", with tokio.
 /// note that how the waiting is done can differ by platform
+///
+/// Since the methods of this trait return boxed futures, prefer the inherent methods of
+/// [`AsyncWaiter`][crate::runtime::AsyncWaiter] (ex. [`exec`][crate::runtime::AsyncWaiter::exec]), which do not allocate.
 #[cfg(feature = "tokio")]
 #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
 #[async_trait]
@@ -118,10 +351,33 @@ pub trait TokioAsyncSituwaition: SituwaitionBase {
     /// Execute the situwaition, and wait until it resolves
     /// or fails with a timeout
     async fn exec(&mut self) -> Result<Self::Result, SituwaitionError<Self::Error>>;
+
+    /// Execute the situwaition like [`TokioAsyncSituwaition::exec`], additionally
+    /// producing a [`WaitReport`] of the attempts that were made
+    ///
+    /// The default implementation only reports how the situwaition ended, without its attempts.
+    async fn exec_with_report(
+        &mut self,
+    ) -> (
+        Result<Self::Result, SituwaitionError<Self::Error>>,
+        WaitReport,
+    )
+    where
+        Self::Error: std::fmt::Display,
+    {
+        let started = std::time::Instant::now();
+        let result = self.exec().await;
+        let report =
+            WaitReport::from_result(self.options().name.clone(), &result, started.elapsed());
+        (result, report)
+    }
 }
 
 /// This trait represents a "situwaition" that can be a"waited", with async-std.
 /// note that how the waiting is done can differ by platform
+///
+/// Since the methods of this trait return boxed futures, prefer the inherent methods of
+/// [`AsyncWaiter`][crate::runtime::AsyncWaiter] (ex. [`exec`][crate::runtime::AsyncWaiter::exec]), which do not allocate.
 #[cfg(feature = "async-std")]
 #[cfg_attr(docsrs, doc(cfg(feature = "async-std")))]
 #[async_trait]
@@ -129,14 +385,83 @@ pub trait AsyncStdAsyncSituwaition: SituwaitionBase {
     /// Execute the situwaition, and wait until it resolves
     /// or fails with a timeout
     async fn exec(&mut self) -> Result<Self::Result, SituwaitionError<Self::Error>>;
+
+    /// Execute the situwaition like [`AsyncStdAsyncSituwaition::exec`], additionally
+    /// producing a [`WaitReport`] of the attempts that were made
+    ///
+    /// The default implementation only reports how the situwaition ended, without its attempts.
+    async fn exec_with_report(
+        &mut self,
+    ) -> (
+        Result<Self::Result, SituwaitionError<Self::Error>>,
+        WaitReport,
+    )
+    where
+        Self::Error: std::fmt::Display,
+    {
+        let started = std::time::Instant::now();
+        let result = self.exec().await;
+        let report =
+            WaitReport::from_result(self.options().name.clone(), &result, started.elapsed());
+        (result, report)
+    }
+}
+
+/// This trait represents a "situwaition" that can be a"waited", with smol.
+/// note that how the waiting is done can differ by platform
+///
+/// Since the methods of this trait return boxed futures, prefer the inherent methods of
+/// [`AsyncWaiter`][crate::runtime::AsyncWaiter] (ex. [`exec`][crate::runtime::AsyncWaiter::exec]), which do not allocate.
+#[cfg(feature = "smol")]
+#[cfg_attr(docsrs, doc(cfg(feature = "smol")))]
+#[async_trait]
+pub trait SmolAsyncSituwaition: SituwaitionBase {
+    /// Execute the situwaition, and wait until it resolves
+    /// or fails with a timeout
+    async fn exec(&mut self) -> Result<Self::Result, SituwaitionError<Self::Error>>;
+
+    /// Execute the situwaition like [`SmolAsyncSituwaition::exec`], additionally
+    /// producing a [`WaitReport`] of the attempts that were made
+    ///
+    /// The default implementation only reports how the situwaition ended, without its attempts.
+    async fn exec_with_report(
+        &mut self,
+    ) -> (
+        Result<Self::Result, SituwaitionError<Self::Error>>,
+        WaitReport,
+    )
+    where
+        Self::Error: std::fmt::Display,
+    {
+        let started = std::time::Instant::now();
+        let result = self.exec().await;
+        let report =
+            WaitReport::from_result(self.options().name.clone(), &result, started.elapsed());
+        (result, report)
+    }
 }
 
 /// Errors that are thrown during waiter creation
+///
+/// More variants may be added in the future, so matches on it need a wildcard arm.
 #[derive(Debug, Clone, Error)]
+#[non_exhaustive]
 pub enum WaiterCreationError {
     #[error("invalid timeout: {0}")]
     InvalidTimeout(String),
 
     #[error("invalid interval: {0}")]
     InvalidInterval(String),
+
+    #[error("invalid cooldown: {0}")]
+    InvalidCooldown(String),
+
+    #[error("missing required field: {0}")]
+    MissingField(String),
+}
+
+impl From<derive_builder::UninitializedFieldError> for WaiterCreationError {
+    fn from(e: derive_builder::UninitializedFieldError) -> Self {
+        WaiterCreationError::MissingField(e.field_name().into())
+    }
 }
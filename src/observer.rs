@@ -0,0 +1,562 @@
+//! Instrumentation shared by all executors
+
+use std::{
+    error::Error,
+    fmt::{self, Debug, Display},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use crate::{
+    handle::Progress,
+    registry,
+    report::{AttemptOutcome, AttemptRecord, WaitOutcome, WaitReport},
+    AttemptInfo, SituwaitionError, SituwaitionHooks, SituwaitionOpts,
+};
+
+#[cfg(feature = "metrics")]
+pub(crate) const METRIC_ATTEMPTS: &str = "situwaition_attempts_total";
+#[cfg(feature = "metrics")]
+pub(crate) const METRIC_TIMEOUTS: &str = "situwaition_timeouts_total";
+#[cfg(feature = "metrics")]
+pub(crate) const METRIC_CHECK_TIMEOUTS: &str = "situwaition_check_timeouts_total";
+#[cfg(feature = "metrics")]
+pub(crate) const METRIC_TIME_TO_READY: &str = "situwaition_time_to_ready_seconds";
+#[cfg(feature = "metrics")]
+pub(crate) const METRIC_IN_FLIGHT: &str = "situwaition_in_flight";
+
+/// Metric handles for a single situwaition, labeled by the situwaition's name (if present)
+#[cfg(feature = "metrics")]
+struct WaitMetrics {
+    attempts: metrics::Counter,
+    timeouts: metrics::Counter,
+    check_timeouts: metrics::Counter,
+    time_to_ready: metrics::Histogram,
+    in_flight: metrics::Gauge,
+}
+
+#[cfg(feature = "metrics")]
+impl WaitMetrics {
+    fn new(opts: &SituwaitionOpts) -> Self {
+        let labels = opts
+            .name
+            .iter()
+            .map(|n| metrics::Label::new("name", n.clone()))
+            .collect::<Vec<_>>();
+
+        WaitMetrics {
+            attempts: metrics::counter!(METRIC_ATTEMPTS, labels.clone()),
+            timeouts: metrics::counter!(METRIC_TIMEOUTS, labels.clone()),
+            check_timeouts: metrics::counter!(METRIC_CHECK_TIMEOUTS, labels.clone()),
+            time_to_ready: metrics::histogram!(METRIC_TIME_TO_READY, labels.clone()),
+            in_flight: metrics::gauge!(METRIC_IN_FLIGHT, labels),
+        }
+    }
+}
+
+/// Tracks the progress of a single situwaition, firing the relevant hooks
+/// (and emitting tracing events and metrics, when enabled).
+///
+/// Executors own the timing (so that runtime-specific clocks are respected), and pass the
+/// elapsed time in with every event.
+pub(crate) struct WaitObserver {
+    hooks: SituwaitionHooks,
+    attempts: AtomicUsize,
+
+    /// Whether the situwaition has finished (successfully or not)
+    finished: AtomicBool,
+
+    /// Time elapsed as of the most recent attempt, for hooks that are run when the situwaition is dropped
+    last_elapsed: Mutex<Duration>,
+
+    /// ID of the situwaition in the global registry (if the registry was enabled)
+    registry_id: Option<u64>,
+
+    /// Report that is being built (if requested), along with the start of the current attempt
+    report: Option<Mutex<(WaitReport, Duration)>>,
+
+    /// Live progress shared with a [`WaitHandle`][crate::handle::WaitHandle] (if the situwaition was spawned)
+    progress: Option<Arc<Mutex<Progress>>>,
+
+    /// The timeout scale factor (see [`crate::scale`]) the situwaition runs with, if scaling is in effect
+    timeout_scale: Option<f64>,
+
+    #[cfg(feature = "metrics")]
+    metrics: WaitMetrics,
+
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+
+    /// The dispatcher that was active when the situwaition was started,
+    /// so that events emitted from other threads end up in the same place
+    #[cfg(feature = "tracing")]
+    dispatch: tracing::Dispatch,
+}
+
+impl WaitObserver {
+    pub(crate) fn new(opts: &SituwaitionOpts) -> Self {
+        #[cfg(feature = "metrics")]
+        let metrics = WaitMetrics::new(opts);
+        #[cfg(feature = "metrics")]
+        metrics.in_flight.increment(1);
+
+        WaitObserver {
+            hooks: opts.hooks.clone(),
+            attempts: AtomicUsize::new(0),
+            finished: AtomicBool::new(false),
+            last_elapsed: Mutex::new(Duration::ZERO),
+            registry_id: registry::register(opts.name.clone()),
+            report: None,
+            progress: None,
+            timeout_scale: None,
+
+            #[cfg(feature = "metrics")]
+            metrics,
+
+            #[cfg(feature = "tracing")]
+            span: tracing::info_span!(
+                "situwaition",
+                name = opts.name.as_deref(),
+                timeout = ?opts.timeout,
+                check_interval = ?opts.check_interval,
+                check_cooldown = ?opts.check_cooldown,
+            ),
+
+            #[cfg(feature = "tracing")]
+            dispatch: tracing::dispatcher::get_default(|d| d.clone()),
+        }
+    }
+
+    /// Note the timeout scale factor the situwaition runs with (captured when it starts),
+    /// for timeout errors and the report
+    pub(crate) fn with_timeout_scale(mut self, timeout_scale: f64) -> Self {
+        self.timeout_scale = (timeout_scale != 1.0).then_some(timeout_scale);
+        self
+    }
+
+    /// Record a [`WaitReport`] as the situwaition progresses
+    pub(crate) fn with_report(mut self, opts: &SituwaitionOpts) -> Self {
+        let report = WaitReport {
+            name: opts.name.clone(),
+            ..WaitReport::default()
+        };
+        self.report = Some(Mutex::new((report, Duration::ZERO)));
+        self
+    }
+
+    /// Share live progress with a handle, as the situwaition progresses
+    pub(crate) fn with_progress(mut self, progress: Arc<Mutex<Progress>>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Update the live progress (if it is being shared)
+    fn update_progress(&self, update_fn: impl FnOnce(&mut Progress)) {
+        if let Some(progress) = &self.progress {
+            update_fn(&mut progress.lock().unwrap_or_else(|e| e.into_inner()));
+        }
+    }
+
+    /// Retrieve the report that was recorded (if one was requested)
+    pub(crate) fn report(&self) -> Option<WaitReport> {
+        self.report.as_ref().map(|r| WaitReport {
+            timeout_scale: self.timeout_scale,
+            ..r.lock().unwrap_or_else(|e| e.into_inner()).0.clone()
+        })
+    }
+
+    /// Update the report (if one is being recorded), as long as the situwaition is still running
+    fn update_report(&self, update_fn: impl FnOnce(&mut WaitReport, Duration)) {
+        if self.finished.load(Ordering::SeqCst) {
+            return;
+        }
+        if let Some(report) = &self.report {
+            let mut guard = report.lock().unwrap_or_else(|e| e.into_inner());
+            let (report, attempt_start) = &mut *guard;
+            update_fn(report, *attempt_start);
+        }
+    }
+
+    /// Record the end of the current attempt in the report
+    fn record_attempt(&self, elapsed: Duration, outcome: AttemptOutcome, error: Option<String>) {
+        let attempt = self.attempts.load(Ordering::SeqCst);
+        self.update_report(|report, started_at| {
+            report.attempts.push(AttemptRecord {
+                attempt,
+                started_at,
+                duration: elapsed.saturating_sub(started_at),
+                outcome,
+                error,
+            })
+        });
+    }
+
+    /// Mark the situwaition as finished, returning whether it had already been finished
+    fn finish(&self) -> bool {
+        let already_finished = self.finished.swap(true, Ordering::SeqCst);
+
+        if let (false, Some(id)) = (already_finished, self.registry_id) {
+            registry::deregister(id);
+        }
+
+        #[cfg(feature = "metrics")]
+        if !already_finished {
+            self.metrics.in_flight.decrement(1);
+        }
+
+        already_finished
+    }
+
+    fn info(&self, elapsed: Duration) -> AttemptInfo {
+        AttemptInfo {
+            attempt: self.attempts.load(Ordering::SeqCst),
+            elapsed,
+        }
+    }
+
+    /// Run code (ex. a check, or a poll of a check) inside the situwaition's span, possibly from another thread
+    pub(crate) fn in_scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        #[cfg(feature = "tracing")]
+        return tracing::dispatcher::with_default(&self.dispatch, || self.span.in_scope(f));
+
+        #[cfg(not(feature = "tracing"))]
+        f()
+    }
+
+    /// Note the time elapsed as of the most recent attempt
+    fn set_last_elapsed(&self, elapsed: Duration) {
+        *self.last_elapsed.lock().unwrap_or_else(|e| e.into_inner()) = elapsed;
+    }
+
+    /// Record the start of a new attempt
+    pub(crate) fn attempt_started(&self, elapsed: Duration) {
+        self.attempts.fetch_add(1, Ordering::SeqCst);
+        self.set_last_elapsed(elapsed);
+        let info = self.info(elapsed);
+
+        #[cfg(feature = "metrics")]
+        self.metrics.attempts.increment(1);
+
+        if let Some(id) = self.registry_id {
+            registry::update(id, |w| w.attempts = info.attempt);
+        }
+        self.update_progress(|p| p.attempts = info.attempt);
+
+        if let Some(report) = &self.report {
+            report.lock().unwrap_or_else(|e| e.into_inner()).1 = elapsed;
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::trace!(parent: &self.span, attempt = info.attempt, elapsed = ?elapsed, "starting attempt");
+
+        if let Some(hook) = &self.hooks.on_attempt {
+            hook(&info);
+        }
+    }
+
+    /// Record the failure of the current attempt
+    pub(crate) fn attempt_failed(&self, elapsed: Duration, err: &dyn Error) {
+        self.set_last_elapsed(elapsed);
+        let info = self.info(elapsed);
+
+        if let Some(id) = self.registry_id {
+            registry::update(id, |w| w.last_error = Some(err.to_string()));
+        }
+        self.update_progress(|p| p.last_error = Some(err.to_string()));
+
+        self.record_attempt(elapsed, AttemptOutcome::Failed, Some(err.to_string()));
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(parent: &self.span, attempt = info.attempt, elapsed = ?elapsed, error = %err, "attempt failed");
+
+        if let Some(hook) = &self.hooks.on_failure {
+            hook(&info, err);
+        }
+    }
+
+    /// Record the success of the current attempt
+    pub(crate) fn succeeded(&self, elapsed: Duration) {
+        self.record_attempt(elapsed, AttemptOutcome::Succeeded, None);
+        self.update_report(|report, _| {
+            report.outcome = WaitOutcome::Succeeded;
+            report.elapsed = elapsed;
+        });
+        self.finish();
+        let info = self.info(elapsed);
+
+        #[cfg(feature = "metrics")]
+        self.metrics.time_to_ready.record(elapsed.as_secs_f64());
+
+        #[cfg(feature = "tracing")]
+        tracing::info!(parent: &self.span, attempts = info.attempt, elapsed = ?elapsed, "situwaition succeeded");
+
+        if let Some(hook) = &self.hooks.on_success {
+            hook(&info);
+        }
+    }
+
+    /// Record a timeout, attaching the timeout scale factor and any diagnostics produced by the timeout hook
+    /// to the error (and the report)
+    pub(crate) fn timed_out<E: Display>(
+        &self,
+        elapsed: Duration,
+        mut err: SituwaitionError<E>,
+    ) -> SituwaitionError<E> {
+        if let SituwaitionError::TimeoutError(_, details)
+        | SituwaitionError::CheckTimeoutError(details) = &mut err
+        {
+            details.timeout_scale = self.timeout_scale;
+        }
+        let outcome = match err {
+            SituwaitionError::CheckTimeoutError(_) => {
+                self.record_attempt(elapsed, AttemptOutcome::TimedOut, None);
+                WaitOutcome::CheckTimedOut
+            }
+            _ => WaitOutcome::TimedOut,
+        };
+        let info = self.info(elapsed);
+        let diagnostics = self.hooks.on_timeout.as_ref().and_then(|hook| hook(&info));
+        self.update_report(|report, _| {
+            report.outcome = outcome;
+            report.elapsed = elapsed;
+            report.error = Some(err.to_string());
+            report.diagnostics = diagnostics.clone();
+        });
+        self.finish();
+
+        #[cfg(feature = "metrics")]
+        match err {
+            SituwaitionError::CheckTimeoutError(_) => self.metrics.check_timeouts.increment(1),
+            _ => self.metrics.timeouts.increment(1),
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::warn!(parent: &self.span, attempts = info.attempt, elapsed = ?elapsed, error = %err, diagnostics = diagnostics.as_deref(), "situwaition timed out");
+
+        if let SituwaitionError::TimeoutError(_, details)
+        | SituwaitionError::CheckTimeoutError(details) = &mut err
+        {
+            details.diagnostics = diagnostics;
+        }
+        err
+    }
+
+    /// Record that the situwaition was cancelled before it could finish
+    pub(crate) fn cancelled<E>(&self, elapsed: Duration) -> SituwaitionError<E> {
+        self.update_report(|report, _| {
+            report.outcome = WaitOutcome::Cancelled;
+            report.elapsed = elapsed;
+        });
+        let already_finished = self.finish();
+
+        #[cfg(feature = "tracing")]
+        tracing::info!(parent: &self.span, attempts = self.attempts.load(Ordering::SeqCst), elapsed = ?elapsed, "situwaition cancelled");
+
+        if let (false, Some(hook)) = (already_finished, &self.hooks.on_cancel) {
+            hook(&self.info(elapsed));
+        }
+
+        SituwaitionError::Cancelled
+    }
+}
+
+/// Error reported by a check whose error type only implements [`Display`] and [`Debug`],
+/// so that it can be passed to hooks (which receive a [`dyn Error`][Error])
+pub(crate) struct CheckError<'a, E>(pub(crate) &'a E);
+
+impl<E: Debug> Debug for CheckError<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<E: Display> Display for CheckError<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<E: Debug + Display> Error for CheckError<'_, E> {}
+
+impl Drop for WaitObserver {
+    fn drop(&mut self) {
+        // Situwaitions that end without an outcome (ex. dropped futures) are no longer in flight,
+        // and count as cancelled
+        if let (false, Some(hook)) = (self.finish(), &self.hooks.on_cancel) {
+            let elapsed = *self
+                .last_elapsed
+                .get_mut()
+                .unwrap_or_else(|e| e.into_inner());
+            hook(&self.info(elapsed));
+        }
+    }
+}
+
+#[cfg(all(test, any(feature = "metrics", feature = "tracing")))]
+mod tests {
+    use std::io::ErrorKind;
+
+    #[cfg(feature = "metrics")]
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+    use super::*;
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_unit_observer_metrics() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+
+        metrics::with_local_recorder(&recorder, || {
+            let observer = WaitObserver::new(&SituwaitionOpts {
+                name: Some("db".into()),
+                ..SituwaitionOpts::default()
+            });
+            observer.attempt_started(Duration::ZERO);
+            observer.attempt_failed(
+                Duration::from_millis(10),
+                &std::io::Error::new(ErrorKind::Other, "test"),
+            );
+            observer.attempt_started(Duration::from_millis(20));
+            observer.succeeded(Duration::from_millis(500));
+        });
+
+        let values = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| {
+                assert!(
+                    key.key()
+                        .labels()
+                        .any(|l| l.key() == "name" && l.value() == "db"),
+                    "metrics are labeled with the situwaition name"
+                );
+                (key.key().name().to_string(), value)
+            })
+            .collect::<std::collections::HashMap<_, _>>();
+
+        assert_eq!(values[METRIC_ATTEMPTS], DebugValue::Counter(2));
+        assert_eq!(values[METRIC_TIMEOUTS], DebugValue::Counter(0));
+        assert_eq!(
+            values[METRIC_TIME_TO_READY],
+            DebugValue::Histogram(vec![0.5.into()])
+        );
+        assert_eq!(values[METRIC_IN_FLIGHT], DebugValue::Gauge(0.0.into()));
+    }
+
+    /// Fields recorded from a span or an event, by name
+    #[cfg(feature = "tracing")]
+    #[derive(Debug, Default)]
+    struct Fields(std::collections::HashMap<String, String>);
+
+    #[cfg(feature = "tracing")]
+    impl tracing::field::Visit for Fields {
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            self.0.insert(field.name().into(), value.into());
+        }
+
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0.insert(field.name().into(), format!("{value:?}"));
+        }
+    }
+
+    /// Subscriber that records every span and event it sees
+    #[cfg(feature = "tracing")]
+    #[derive(Default)]
+    struct Capture {
+        spans: Mutex<Vec<(&'static str, Fields)>>,
+        events: Mutex<Vec<(Option<tracing::span::Id>, Fields)>>,
+    }
+
+    #[cfg(feature = "tracing")]
+    impl tracing::Subscriber for Capture {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let mut fields = Fields::default();
+            span.record(&mut fields);
+            let mut spans = self.spans.lock().unwrap();
+            spans.push((span.metadata().name(), fields));
+            tracing::span::Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            let mut fields = Fields::default();
+            event.record(&mut fields);
+            self.events
+                .lock()
+                .unwrap()
+                .push((event.parent().cloned(), fields));
+        }
+
+        fn enter(&self, _: &tracing::span::Id) {}
+
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_unit_observer_tracing() {
+        let capture = Arc::new(Capture::default());
+
+        tracing::subscriber::with_default(capture.clone(), || {
+            let observer = WaitObserver::new(&SituwaitionOpts {
+                name: Some("db".into()),
+                ..SituwaitionOpts::default()
+            });
+            observer.attempt_started(Duration::ZERO);
+            observer.attempt_failed(
+                Duration::from_millis(10),
+                &std::io::Error::new(ErrorKind::Other, "not ready"),
+            );
+            observer.attempt_started(Duration::from_millis(20));
+            observer.succeeded(Duration::from_millis(30));
+        });
+
+        let spans = capture.spans.lock().unwrap();
+        assert_eq!(spans.len(), 1, "one span per situwaition");
+        let (span_name, span_fields) = &spans[0];
+        assert_eq!(*span_name, "situwaition");
+        assert_eq!(
+            span_fields.0.get("name").map(String::as_str),
+            Some("db"),
+            "span carries the situwaition name"
+        );
+
+        let events = capture.events.lock().unwrap();
+        let messages = events
+            .iter()
+            .map(|(parent, fields)| {
+                assert_eq!(
+                    parent.as_ref().map(|id| id.into_u64()),
+                    Some(1),
+                    "events are emitted inside the situwaition span"
+                );
+                fields.0["message"].as_str()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            [
+                "starting attempt",
+                "attempt failed",
+                "starting attempt",
+                "situwaition succeeded"
+            ]
+        );
+        assert_eq!(events[1].1 .0["attempt"], "1");
+        assert_eq!(events[1].1 .0["error"], "not ready");
+        assert_eq!(events[2].1 .0["attempt"], "2");
+        assert_eq!(events[3].1 .0["attempts"], "2");
+    }
+}
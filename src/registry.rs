@@ -0,0 +1,244 @@
+//! Opt-in global registry of in-flight situwaitions
+//!
+//! When a process (or test binary) hangs, it can be hard to tell which situwaition is stuck.
+//! Once the registry is [enabled][enable], every situwaition that starts is tracked until it finishes,
+//! and the list of active situwaitions can be retrieved with [`active_waits`] or printed with [`dump`].
+//!
+//! ```
+//! situwaition::registry::enable();
+//!
+//! // ... later, ex. from a test timeout hook
+//! eprintln!("{}", situwaition::registry::dump());
+//! ```
+//!
+//! Note that the registry is protected by a [`Mutex`], so it is *not* safe to read from inside
+//! a signal handler itself -- forward the signal to a regular thread (ex. with [`signal-hook`'s iterator][signal-hook])
+//! and dump the registry from there.
+//!
+//! [signal-hook]: https://docs.rs/signal-hook
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::Write,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex, MutexGuard,
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+static ACTIVE_WAITS: Mutex<BTreeMap<u64, ActiveWait>> = Mutex::new(BTreeMap::new());
+
+/// A situwaition that is currently being waited on
+#[derive(Debug, Clone)]
+pub struct ActiveWait {
+    /// Unique (per-process) ID of the situwaition
+    pub id: u64,
+
+    /// The name of the situwaition (see [`SituwaitionOpts::name`][crate::SituwaitionOpts::name])
+    pub name: Option<String>,
+
+    /// When the situwaition was started
+    pub started_at: SystemTime,
+
+    /// The number of attempts that have been started so far
+    pub attempts: usize,
+
+    /// The error produced by the most recent failed attempt
+    pub last_error: Option<String>,
+
+    /// Used to calculate how long the situwaition has been running
+    started: Instant,
+}
+
+impl ActiveWait {
+    /// How long the situwaition has been running
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
+impl fmt::Display for ActiveWait {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {} (running for {:?}, {} attempts",
+            self.id,
+            self.name.as_deref().unwrap_or("<unnamed>"),
+            self.elapsed(),
+            self.attempts,
+        )?;
+        match &self.last_error {
+            Some(e) => write!(f, ", last error: {e})"),
+            None => write!(f, ")"),
+        }
+    }
+}
+
+/// Start tracking situwaitions in the global registry
+pub fn enable() {
+    ENABLED.store(true, Ordering::SeqCst);
+}
+
+/// Stop tracking newly started situwaitions (situwaitions that are already tracked remain so until they finish)
+pub fn disable() {
+    ENABLED.store(false, Ordering::SeqCst);
+}
+
+/// Whether the global registry is enabled
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// Retrieve the situwaitions that are currently in flight, oldest first
+pub fn active_waits() -> Vec<ActiveWait> {
+    lock().values().cloned().collect()
+}
+
+/// Produce a human readable listing of the situwaitions that are currently in flight
+pub fn dump() -> String {
+    let waits = active_waits();
+    let mut out = format!("{} active situwaition(s)", waits.len());
+    for wait in waits {
+        out.push_str(&format!("\n  {wait}"));
+    }
+    out
+}
+
+/// Write a human readable listing of the situwaitions that are currently in flight
+pub fn dump_to(mut w: impl Write) -> std::io::Result<()> {
+    writeln!(w, "{}", dump())
+}
+
+fn lock() -> MutexGuard<'static, BTreeMap<u64, ActiveWait>> {
+    // A panic in a hook shouldn't render the registry unusable
+    ACTIVE_WAITS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Add a situwaition to the registry (if enabled), returning its ID
+pub(crate) fn register(name: Option<String>) -> Option<u64> {
+    if !is_enabled() {
+        return None;
+    }
+
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+    lock().insert(
+        id,
+        ActiveWait {
+            id,
+            name,
+            started_at: SystemTime::now(),
+            attempts: 0,
+            last_error: None,
+            started: Instant::now(),
+        },
+    );
+    Some(id)
+}
+
+/// Update a registered situwaition
+pub(crate) fn update(id: u64, update_fn: impl FnOnce(&mut ActiveWait)) {
+    if let Some(wait) = lock().get_mut(&id) {
+        update_fn(wait);
+    }
+}
+
+/// Remove a situwaition from the registry
+pub(crate) fn deregister(id: u64) {
+    lock().remove(&id);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use crate::{observer::WaitObserver, SituwaitionOpts};
+
+    use super::*;
+
+    fn find(name: &str) -> Option<ActiveWait> {
+        active_waits()
+            .into_iter()
+            .find(|w| w.name.as_deref() == Some(name))
+    }
+
+    /// Serializes the tests that enable the registry
+    static ENABLED_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Keeps the registry enabled for as long as it is alive, so that a failing test doesn't leave it enabled
+    struct Enabled {
+        _lock: MutexGuard<'static, ()>,
+    }
+
+    impl Enabled {
+        fn new() -> Self {
+            let guard = ENABLED_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            enable();
+            Enabled { _lock: guard }
+        }
+    }
+
+    impl Drop for Enabled {
+        fn drop(&mut self) {
+            disable();
+        }
+    }
+
+    #[test]
+    fn test_unit_registry_tracks_active_waits() {
+        let _enabled = Enabled::new();
+
+        let observer = WaitObserver::new(&SituwaitionOpts {
+            name: Some("test_unit_registry_tracks_active_waits".into()),
+            ..SituwaitionOpts::default()
+        });
+        observer.attempt_started(Duration::ZERO);
+        observer.attempt_failed(
+            Duration::from_millis(10),
+            &std::io::Error::new(ErrorKind::Other, "not ready"),
+        );
+
+        let wait = find("test_unit_registry_tracks_active_waits").expect("wait is registered");
+        assert_eq!(wait.attempts, 1, "attempts are tracked");
+        assert_eq!(
+            wait.last_error.as_deref(),
+            Some("not ready"),
+            "last error is tracked"
+        );
+        assert!(
+            dump().contains("test_unit_registry_tracks_active_waits"),
+            "dump includes the wait"
+        );
+
+        observer.attempt_started(Duration::from_millis(20));
+        observer.succeeded(Duration::from_millis(30));
+        assert!(
+            find("test_unit_registry_tracks_active_waits").is_none(),
+            "finished wait is removed"
+        );
+    }
+
+    #[test]
+    fn test_unit_registry_removes_dropped_waits() {
+        let _enabled = Enabled::new();
+
+        let observer = WaitObserver::new(&SituwaitionOpts {
+            name: Some("test_unit_registry_removes_dropped_waits".into()),
+            ..SituwaitionOpts::default()
+        });
+        observer.attempt_started(Duration::ZERO);
+        assert!(
+            find("test_unit_registry_removes_dropped_waits").is_some(),
+            "wait is registered"
+        );
+
+        drop(observer);
+        assert!(
+            find("test_unit_registry_removes_dropped_waits").is_none(),
+            "dropped wait is removed"
+        );
+    }
+}
@@ -0,0 +1,336 @@
+//! Structured reports on how a situwaition played out
+//!
+//! Reports are produced by the `exec_with_report` method of each executor
+//! (ex. [`SyncSituwaition::exec_with_report`][crate::SyncSituwaition::exec_with_report]),
+//! and contain every attempt along with some summary statistics. The [`Display`][fmt::Display]
+//! implementation renders a timeline that is suitable for test failure output.
+
+use std::{fmt, time::Duration};
+
+use crate::SituwaitionError;
+
+/// How a single attempt ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum AttemptOutcome {
+    /// The check passed
+    Succeeded,
+
+    /// The check failed
+    Failed,
+
+    /// The check was still running when the situwaition timed out
+    TimedOut,
+}
+
+/// A single attempt (run of the check function)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AttemptRecord {
+    /// The number of the attempt (the first attempt is 1)
+    pub attempt: usize,
+
+    /// When the attempt started, relative to the start of the situwaition
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "started_at_ms",
+            serialize_with = "crate::export::serialize_millis"
+        )
+    )]
+    pub started_at: Duration,
+
+    /// How long the check took
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "duration_ms",
+            serialize_with = "crate::export::serialize_millis"
+        )
+    )]
+    pub duration: Duration,
+
+    /// How the attempt ended
+    pub outcome: AttemptOutcome,
+
+    /// The error produced by the check (for failed attempts)
+    pub error: Option<String>,
+}
+
+/// How the situwaition as a whole ended
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum WaitOutcome {
+    /// The situwaition did not finish (ex. due to an unexpected error)
+    #[default]
+    Unfinished,
+
+    /// An attempt succeeded
+    Succeeded,
+
+    /// Attempts failed repeatedly until the timeout
+    TimedOut,
+
+    /// The timeout was reached while a check was running
+    CheckTimedOut,
+
+    /// The situwaition was cancelled before it could finish
+    Cancelled,
+}
+
+/// Report of the attempts that were made during a situwaition
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WaitReport {
+    /// The name of the situwaition (see [`SituwaitionOpts::name`][crate::SituwaitionOpts::name])
+    pub name: Option<String>,
+
+    /// How the situwaition ended
+    pub outcome: WaitOutcome,
+
+    /// Total time spent on the situwaition
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "elapsed_ms",
+            serialize_with = "crate::export::serialize_millis"
+        )
+    )]
+    pub elapsed: Duration,
+
+    /// Every attempt that was made, in order
+    pub attempts: Vec<AttemptRecord>,
+
+    /// The error the situwaition ended with, if any
+    pub error: Option<String>,
+
+    /// Text produced by the `on_timeout` hook (see [`SituwaitionHooks`][crate::SituwaitionHooks]), if any
+    pub diagnostics: Option<String>,
+
+    /// The timeout scale factor (see [`crate::scale`]) that was in effect when the situwaition started,
+    /// if scaling was in effect
+    pub timeout_scale: Option<f64>,
+}
+
+impl WaitReport {
+    /// Summarize how a situwaition ended, for executors that do not record their attempts
+    pub(crate) fn from_result<R, E: fmt::Display>(
+        name: Option<String>,
+        result: &Result<R, SituwaitionError<E>>,
+        elapsed: Duration,
+    ) -> Self {
+        let outcome = match result {
+            Ok(_) => WaitOutcome::Succeeded,
+            Err(SituwaitionError::TimeoutError(..)) => WaitOutcome::TimedOut,
+            Err(SituwaitionError::CheckTimeoutError(_)) => WaitOutcome::CheckTimedOut,
+            Err(SituwaitionError::Cancelled) => WaitOutcome::Cancelled,
+            Err(_) => WaitOutcome::Unfinished,
+        };
+        WaitReport {
+            name,
+            outcome,
+            elapsed,
+            error: result.as_ref().err().map(|e| e.to_string()),
+            ..WaitReport::default()
+        }
+    }
+
+    /// Check latency at a given percentile (0-100, nearest-rank), if any attempts were made
+    pub fn check_latency_percentile(&self, percentile: f64) -> Option<Duration> {
+        let mut latencies = self.attempts.iter().map(|a| a.duration).collect::<Vec<_>>();
+        if latencies.is_empty() {
+            return None;
+        }
+        latencies.sort();
+
+        let rank =
+            ((percentile.clamp(0.0, 100.0) / 100.0) * latencies.len() as f64).ceil() as usize;
+        Some(latencies[rank.saturating_sub(1)])
+    }
+
+    /// Median check latency
+    pub fn p50_check_latency(&self) -> Option<Duration> {
+        self.check_latency_percentile(50.0)
+    }
+
+    /// 99th percentile check latency
+    pub fn p99_check_latency(&self) -> Option<Duration> {
+        self.check_latency_percentile(99.0)
+    }
+
+    /// Total time spent outside of checks (cooldowns and check intervals)
+    pub fn total_sleep(&self) -> Duration {
+        self.elapsed
+            .saturating_sub(self.attempts.iter().map(|a| a.duration).sum())
+    }
+}
+
+impl fmt::Display for WaitReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outcome = match self.outcome {
+            WaitOutcome::Unfinished => "did not finish",
+            WaitOutcome::Succeeded => "succeeded",
+            WaitOutcome::TimedOut => "timed out",
+            WaitOutcome::CheckTimedOut => "timed out during a check",
+            WaitOutcome::Cancelled => "was cancelled",
+        };
+        write!(
+            f,
+            "situwaition {} {outcome} after {:?} ({} attempts",
+            self.name.as_deref().unwrap_or("<unnamed>"),
+            self.elapsed,
+            self.attempts.len(),
+        )?;
+        if let (Some(p50), Some(p99)) = (self.p50_check_latency(), self.p99_check_latency()) {
+            write!(f, ", p50 check {p50:?}, p99 check {p99:?}")?;
+        }
+        write!(f, ", {:?} sleeping)", self.total_sleep())?;
+
+        for attempt in &self.attempts {
+            write!(
+                f,
+                "\n  #{:<4} +{:<12} took {:<12} ",
+                attempt.attempt,
+                format!("{:?}", attempt.started_at),
+                format!("{:?}", attempt.duration),
+            )?;
+            match (attempt.outcome, &attempt.error) {
+                (AttemptOutcome::Succeeded, _) => write!(f, "succeeded")?,
+                (AttemptOutcome::TimedOut, _) => write!(f, "timed out")?,
+                (AttemptOutcome::Failed, Some(e)) => write!(f, "failed: {e}")?,
+                (AttemptOutcome::Failed, None) => write!(f, "failed")?,
+            }
+        }
+
+        if let Some(e) = &self.error {
+            write!(f, "\n  error: {e}")?;
+        }
+        if let Some(d) = &self.diagnostics {
+            write!(f, "\n  diagnostics: {d}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(attempt: usize, started_at: u64, duration: u64) -> AttemptRecord {
+        AttemptRecord {
+            attempt,
+            started_at: Duration::from_millis(started_at),
+            duration: Duration::from_millis(duration),
+            outcome: AttemptOutcome::Failed,
+            error: Some("not ready".into()),
+        }
+    }
+
+    #[test]
+    fn test_unit_report_stats() {
+        let report = WaitReport {
+            name: Some("db".into()),
+            outcome: WaitOutcome::TimedOut,
+            elapsed: Duration::from_millis(1_000),
+            attempts: (1..=10)
+                .map(|n| attempt(n, n as u64 * 100, n as u64 * 10))
+                .collect(),
+            error: None,
+            diagnostics: None,
+            timeout_scale: None,
+        };
+
+        assert_eq!(report.p50_check_latency(), Some(Duration::from_millis(50)));
+        assert_eq!(report.p99_check_latency(), Some(Duration::from_millis(100)));
+        assert_eq!(report.total_sleep(), Duration::from_millis(450));
+        assert_eq!(WaitReport::default().p50_check_latency(), None);
+    }
+
+    #[test]
+    fn test_unit_report_display() {
+        let report = WaitReport {
+            name: Some("db".into()),
+            outcome: WaitOutcome::TimedOut,
+            elapsed: Duration::from_millis(300),
+            attempts: vec![attempt(1, 0, 10), attempt(2, 260, 10)],
+            error: Some(
+                "failed repeatedly until the timeout: not ready (timeouts scaled by 1.5x)".into(),
+            ),
+            diagnostics: Some("db container exited".into()),
+            timeout_scale: Some(1.5),
+        };
+
+        let output = report.to_string();
+        assert!(
+            output.starts_with("situwaition db timed out after 300ms (2 attempts"),
+            "summary line is present"
+        );
+        assert_eq!(
+            output.lines().count(),
+            5,
+            "summary, attempt, error and diagnostics lines are present"
+        );
+        assert!(output.contains("failed: not ready"), "errors are shown");
+        assert!(
+            output.contains(
+                "error: failed repeatedly until the timeout: not ready (timeouts scaled by 1.5x)"
+            ),
+            "scale factor is shown with the error"
+        );
+        assert_eq!(
+            output.matches("scaled by").count(),
+            1,
+            "scale factor is shown once"
+        );
+        assert!(
+            output.ends_with("diagnostics: db container exited"),
+            "diagnostics are shown"
+        );
+    }
+
+    /// Situwaition that only implements [`SyncSituwaition::exec`][crate::SyncSituwaition::exec]
+    struct AlwaysReady(crate::SituwaitionOpts);
+
+    impl crate::SituwaitionBase for AlwaysReady {
+        type Result = &'static str;
+        type Error = std::io::Error;
+
+        fn options(&self) -> &crate::SituwaitionOpts {
+            &self.0
+        }
+
+        fn set_options(
+            &mut self,
+            update_fn: impl Fn(&crate::SituwaitionOpts) -> crate::SituwaitionOpts,
+        ) -> Result<(), SituwaitionError<()>> {
+            self.0 = update_fn(&self.0);
+            Ok(())
+        }
+    }
+
+    impl crate::SyncSituwaition for AlwaysReady {
+        fn exec(&mut self) -> Result<&'static str, SituwaitionError<std::io::Error>> {
+            Ok("ready")
+        }
+    }
+
+    #[test]
+    fn test_unit_default_exec_with_report() {
+        use crate::SyncSituwaition;
+
+        let mut situwaition = AlwaysReady(crate::SituwaitionOpts {
+            name: Some("db".into()),
+            ..crate::SituwaitionOpts::default()
+        });
+        let (result, report) = situwaition.exec_with_report();
+
+        assert!(matches!(result, Ok("ready")), "result is passed through");
+        assert_eq!(report.name.as_deref(), Some("db"));
+        assert_eq!(report.outcome, WaitOutcome::Succeeded);
+        assert!(report.attempts.is_empty(), "attempts are not recorded");
+        assert_eq!(report.error, None);
+    }
+}
@@ -1,13 +1,50 @@
 #![cfg(feature = "async-std")]
 
-use std::{error::Error, future::Future, time::Instant};
+use std::{
+    error::Error,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
 
-use async_std::{future::timeout, task::sleep};
 use async_trait::async_trait;
 
-use crate::{AsyncStdAsyncSituwaition, SituwaitionError};
+use crate::{AsyncStdAsyncSituwaition, SituwaitionError, WaitReport};
 
-use super::AsyncWaiter;
+use super::{AsyncRuntime, AsyncWaiter, Runtime};
+
+/// [`Runtime`] for async-std
+///
+/// async-std's own timers are backed by async-io, which is used directly so that sleeps are nameable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(docsrs, doc(cfg(feature = "async-std")))]
+pub struct AsyncStdRuntime;
+
+/// Sleep produced by [`AsyncStdRuntime`]
+#[derive(Debug)]
+#[cfg_attr(docsrs, doc(cfg(feature = "async-std")))]
+pub struct Sleep(async_io::Timer);
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        Pin::new(&mut self.0).poll(cx).map(|_| ())
+    }
+}
+
+impl Runtime for AsyncStdRuntime {
+    type Sleep = Sleep;
+
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        Sleep(async_io::Timer::after(duration))
+    }
+}
 
 #[async_trait]
 #[cfg_attr(docsrs, doc(cfg(feature = "async-std")))]
@@ -19,32 +56,11 @@ where
     E: Error + Send + Sync,
 {
     async fn exec(&mut self) -> Result<R, SituwaitionError<E>> {
-        let start = Instant::now();
-        let check_timeout = self.opts.timeout;
-        let cooldown = self.opts.check_cooldown;
-
-        loop {
-            let fut = (self.factory)();
-            match timeout(check_timeout, fut).await {
-                // Check completed in time and successfully and we can return
-                Ok(Ok(v)) => return Ok(v),
-                // Check timed out
-                Err(_) => return Err(SituwaitionError::CheckTimeoutError),
-                // Check completed in time but failed
-                Ok(Err(e)) => {
-                    if let Some(t) = cooldown {
-                        sleep(t).await;
-                    }
-
-                    if Instant::now() - start > self.opts.timeout {
-                        return Err(SituwaitionError::TimeoutError(e));
-                    }
-
-                    // If we got a condition failure, sleep and try again in the next loop
-                    sleep(self.opts.check_interval).await;
-                }
-            }
-        }
+        self.exec_on(AsyncRuntime::AsyncStd).await
+    }
+
+    async fn exec_with_report(&mut self) -> (Result<R, SituwaitionError<E>>, WaitReport) {
+        self.exec_with_report_on(AsyncRuntime::AsyncStd).await
     }
 }
 
@@ -61,12 +77,28 @@ where
     F: Fn() -> G + Send,
     G: Future<Output = Result<R, E>> + Send,
 {
-    AsyncWaiter::from_factory(factory).exec().await
+    AsyncWaiter::from_factory(factory)
+        .exec_on(AsyncRuntime::AsyncStd)
+        .await
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{io::ErrorKind, time::Duration};
+    use std::{
+        io::ErrorKind,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::{Duration, Instant},
+    };
+
+    use async_std::task::sleep;
+
+    use crate::{
+        report::{AttemptOutcome, WaitOutcome},
+        SituwaitionHooks,
+    };
 
     use super::*;
 
@@ -106,7 +138,7 @@ mod tests {
             .expect("failed to create")
             .exec()
             .await,
-            Err(SituwaitionError::TimeoutError(std::io::Error { .. })),
+            Err(SituwaitionError::TimeoutError(std::io::Error { .. }, _)),
         ),);
     }
 
@@ -142,7 +174,7 @@ mod tests {
                 .expect("failed to create")
                 .exec()
                 .await,
-                Err(SituwaitionError::TimeoutError(std::io::Error { .. })),
+                Err(SituwaitionError::TimeoutError(std::io::Error { .. }, _)),
             ),
             "always erroring check fails"
         );
@@ -175,6 +207,82 @@ mod tests {
         );
     }
 
+    #[async_std::test]
+    async fn test_unit_async_std_hooks() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let successes = Arc::new(AtomicUsize::new(0));
+        let (shared_attempts, shared_successes) = (attempts.clone(), successes.clone());
+        let count = Arc::new(AtomicUsize::new(0));
+
+        let result = AsyncWaiter::with_check_interval(
+            || async {
+                match count.fetch_add(1, Ordering::SeqCst) {
+                    2 => Ok(true),
+                    _ => Err(std::io::Error::new(ErrorKind::Other, "test")),
+                }
+            },
+            Duration::from_millis(10),
+        )
+        .expect("failed to create")
+        .with_hooks(
+            SituwaitionHooks::new()
+                .on_attempt(move |_| {
+                    shared_attempts.fetch_add(1, Ordering::SeqCst);
+                })
+                .on_success(move |info| {
+                    assert_eq!(info.attempt, 3, "success happened on the third attempt");
+                    shared_successes.fetch_add(1, Ordering::SeqCst);
+                }),
+        )
+        .exec()
+        .await;
+
+        assert!(matches!(result, Ok(true)), "check eventually passed");
+        assert_eq!(
+            attempts.load(Ordering::SeqCst),
+            3,
+            "attempt hook ran per attempt"
+        );
+        assert_eq!(successes.load(Ordering::SeqCst), 1, "success hook ran once");
+    }
+
+    #[async_std::test]
+    async fn test_unit_async_std_exec_with_report() {
+        let count = AtomicUsize::new(0);
+        let (result, report) = AsyncWaiter::with_check_interval(
+            || async {
+                match count.fetch_add(1, Ordering::SeqCst) {
+                    2 => Ok(true),
+                    _ => Err(std::io::Error::new(ErrorKind::Other, "test")),
+                }
+            },
+            Duration::from_millis(50),
+        )
+        .expect("failed to create")
+        .exec_with_report()
+        .await;
+
+        assert!(matches!(result, Ok(true)), "check eventually passed");
+        assert_eq!(report.outcome, WaitOutcome::Succeeded);
+        assert_eq!(
+            report
+                .attempts
+                .iter()
+                .map(|a| a.outcome)
+                .collect::<Vec<_>>(),
+            vec![
+                AttemptOutcome::Failed,
+                AttemptOutcome::Failed,
+                AttemptOutcome::Succeeded
+            ],
+            "every attempt was recorded"
+        );
+        assert!(
+            report.total_sleep() >= Duration::from_millis(100),
+            "intervals are counted as sleep"
+        );
+    }
+
     #[async_std::test]
     async fn test_unit_async_std_with_long_check() {
         let start = Instant::now();
@@ -190,7 +298,7 @@ mod tests {
                 .expect("failed to create")
                 .exec()
                 .await,
-                Err(SituwaitionError::CheckTimeoutError),
+                Err(SituwaitionError::CheckTimeoutError(_)),
             ),
             "check that finishes in 500ms times out in 100ms as configured"
         );
@@ -199,4 +307,34 @@ mod tests {
             "timed out before the check completed"
         );
     }
+
+    /// With both runtime features enabled, the runtime that is driving the waiter is detected
+    #[cfg(all(feature = "tokio", feature = "async-std"))]
+    #[async_std::test]
+    async fn test_unit_async_std_detect_runtime() {
+        assert_eq!(
+            crate::runtime::AsyncRuntime::detect(),
+            crate::runtime::AsyncRuntime::AsyncStd
+        );
+        assert!(
+            matches!(
+                AsyncWaiter::from_factory(|| async { Ok::<bool, std::io::Error>(true) })
+                    .exec()
+                    .await,
+                Ok(true)
+            ),
+            "exec is unambiguous and runs on the detected runtime"
+        );
+    }
+
+    #[async_std::test]
+    async fn test_unit_async_std_await_waiter() {
+        let result = AsyncWaiter::with_timeout(
+            || async { Ok::<bool, std::io::Error>(true) },
+            Duration::from_millis(500),
+        )
+        .expect("failed to create")
+        .await;
+        assert!(matches!(result, Ok(true)), "waiter can be awaited directly");
+    }
 }
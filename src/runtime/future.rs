@@ -0,0 +1,288 @@
+//! Poll-based future that drives an [`AsyncWaiter`][super::AsyncWaiter]
+//!
+//! The same state machine is used for every [`Runtime`], which only provides the clock and timers.
+//! Since the future is a named type (rather than a boxed `async` block), no allocation is made
+//! per execution, and it can be stored or used inside `select!`.
+
+use std::{
+    error::Error,
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use pin_project_lite::pin_project;
+
+use situwaition_core::{Action, Schedule};
+
+#[cfg(feature = "tokio")]
+use std::sync::{Arc, Mutex};
+
+use super::Runtime;
+#[cfg(feature = "tokio")]
+use crate::handle::Progress;
+use crate::{
+    cancel::WaitForCancellation, observer::WaitObserver, SituwaitionError, SituwaitionOpts,
+    WaitReport,
+};
+
+/// Error produced by a [`Timeout`] whose deadline passed before the future completed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elapsed;
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("deadline has elapsed")
+    }
+}
+
+impl Error for Elapsed {}
+
+pin_project! {
+    /// Future that races a future against a sleep, produced by [`Runtime::timeout`]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct Timeout<F, S> {
+        #[pin]
+        future: F,
+        #[pin]
+        deadline: S,
+    }
+}
+
+impl<F, S> Timeout<F, S> {
+    /// Race `future` against `deadline` (usually a [`Runtime::sleep`])
+    pub fn new(future: F, deadline: S) -> Self {
+        Timeout { future, deadline }
+    }
+}
+
+impl<F, S> Future for Timeout<F, S>
+where
+    F: Future,
+    S: Future<Output = ()>,
+{
+    type Output = Result<F::Output, Elapsed>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        if let Poll::Ready(v) = this.future.poll(cx) {
+            return Poll::Ready(Ok(v));
+        }
+        this.deadline.poll(cx).map(|_| Err(Elapsed))
+    }
+}
+
+pin_project! {
+    /// Future that runs a situwaition to completion,
+    /// produced by [`AsyncWaiter::exec`][super::AsyncWaiter::exec] (or by awaiting an [`AsyncWaiter`][super::AsyncWaiter])
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct WaitFuture<A, F, E, T>
+    where
+        T: Runtime,
+    {
+        factory: A,
+        runtime: T,
+        schedule: Schedule,
+        observer: WaitObserver,
+
+        // Completes once the situwaition's cancellation token (if any) is cancelled
+        cancellation: Option<WaitForCancellation>,
+
+        // When the future was first polled, which times given to the schedule are measured from
+        origin: Option<Instant>,
+
+        // Error produced by the most recent check, kept until the schedule decides to retry or give up
+        failure: Option<E>,
+
+        // The check that is currently running, raced against the timeout
+        #[pin]
+        check: Option<Timeout<F, T::Sleep>>,
+
+        // The sleep (cooldown or check interval) that is currently running
+        #[pin]
+        sleep: Option<T::Sleep>,
+    }
+}
+
+impl<A, F, R, E, T> WaitFuture<A, F, E, T>
+where
+    A: FnMut() -> F,
+    F: Future<Output = Result<R, E>>,
+    E: Error,
+    T: Runtime,
+{
+    /// Create a future for a situwaition with the given options (before any process-wide scaling)
+    pub(crate) fn new(factory: A, opts: &SituwaitionOpts, runtime: T, report: bool) -> Self {
+        let (opts, timeout_scale) = opts.scaled_with_factor();
+        let observer = WaitObserver::new(&opts).with_timeout_scale(timeout_scale);
+        let observer = match report {
+            true => observer.with_report(&opts),
+            false => observer,
+        };
+
+        WaitFuture {
+            factory,
+            runtime,
+            schedule: Schedule::new(opts.policy()),
+            cancellation: opts.cancellation.as_ref().map(|t| t.cancelled()),
+            observer,
+            origin: None,
+            failure: None,
+            check: None,
+            sleep: None,
+        }
+    }
+
+    /// Share live progress with a handle (see [`crate::handle`])
+    #[cfg(feature = "tokio")]
+    pub(crate) fn with_progress(mut self, progress: Arc<Mutex<Progress>>) -> Self {
+        self.observer = self.observer.with_progress(progress);
+        self
+    }
+}
+
+impl<A, F, R, E, T> Future for WaitFuture<A, F, E, T>
+where
+    A: FnMut() -> F,
+    F: Future<Output = Result<R, E>>,
+    E: Error,
+    T: Runtime,
+{
+    type Output = Result<R, SituwaitionError<E>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        let origin = *this.origin.get_or_insert_with(|| this.runtime.now());
+        let runtime = &*this.runtime;
+        let now = || runtime.now().saturating_duration_since(origin);
+
+        // Cancellation takes priority, dropping the check or sleep that is in flight
+        if let Some(cancellation) = this.cancellation.as_mut() {
+            if Pin::new(cancellation).poll(cx).is_ready() {
+                this.check.set(None);
+                this.sleep.set(None);
+                return Poll::Ready(Err(this.observer.cancelled(this.schedule.elapsed(now()))));
+            }
+        }
+
+        loop {
+            // Wait on the check that is currently running, if any
+            if let Some(check) = this.check.as_mut().as_pin_mut() {
+                let elapsed = this.schedule.elapsed(now());
+                match this.observer.in_scope(|| check.poll(cx)) {
+                    Poll::Pending => return Poll::Pending,
+
+                    // Check completed in time and successfully and we can return
+                    Poll::Ready(Ok(Ok(v))) => {
+                        this.check.set(None);
+                        this.schedule.succeeded();
+                        this.observer.succeeded(elapsed);
+                        return Poll::Ready(Ok(v));
+                    }
+
+                    // Check completed in time but failed
+                    Poll::Ready(Ok(Err(e))) => {
+                        this.check.set(None);
+                        this.schedule.failed();
+                        this.observer.attempt_failed(elapsed, &e);
+                        *this.failure = Some(e);
+                    }
+
+                    // Check did not complete before the timeout
+                    Poll::Ready(Err(Elapsed)) => {
+                        this.check.set(None);
+                        return Poll::Ready(Err(this.observer.timed_out(
+                            elapsed,
+                            SituwaitionError::CheckTimeoutError(Default::default()),
+                        )));
+                    }
+                }
+            }
+
+            // Wait out the sleep that is currently running, if any
+            if let Some(sleep) = this.sleep.as_mut().as_pin_mut() {
+                if sleep.poll(cx).is_pending() {
+                    return Poll::Pending;
+                }
+                this.sleep.set(None);
+            }
+
+            let now = now();
+            match this.schedule.next(now) {
+                Action::Check { timeout, .. } => {
+                    *this.failure = None;
+                    this.observer.attempt_started(this.schedule.elapsed(now));
+                    let factory = &mut *this.factory;
+                    let check = this.observer.in_scope(factory);
+                    this.check.set(Some(runtime.timeout(timeout, check)));
+                }
+
+                Action::Sleep(duration) => this.sleep.set(Some(runtime.sleep(duration))),
+
+                Action::TimedOut => {
+                    let e = this
+                        .failure
+                        .take()
+                        .expect("failure is present after a check");
+                    return Poll::Ready(Err(this.observer.timed_out(
+                        this.schedule.elapsed(now),
+                        SituwaitionError::TimeoutError(e, Default::default()),
+                    )));
+                }
+
+                Action::Done => panic!("WaitFuture polled after completion"),
+            }
+        }
+    }
+}
+
+pin_project! {
+    /// Future that runs a situwaition to completion, producing a [`WaitReport`] along with the result,
+    /// produced by [`AsyncWaiter::exec_with_report`][super::AsyncWaiter::exec_with_report]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct WaitReportFuture<A, F, E, T>
+    where
+        T: Runtime,
+    {
+        #[pin]
+        inner: WaitFuture<A, F, E, T>,
+    }
+}
+
+impl<A, F, R, E, T> WaitReportFuture<A, F, E, T>
+where
+    A: FnMut() -> F,
+    F: Future<Output = Result<R, E>>,
+    E: Error,
+    T: Runtime,
+{
+    /// Create a future for a situwaition with the given options (before any process-wide scaling)
+    pub(crate) fn new(factory: A, opts: &SituwaitionOpts, runtime: T) -> Self {
+        WaitReportFuture {
+            inner: WaitFuture::new(factory, opts, runtime, true),
+        }
+    }
+}
+
+impl<A, F, R, E, T> Future for WaitReportFuture<A, F, E, T>
+where
+    A: FnMut() -> F,
+    F: Future<Output = Result<R, E>>,
+    E: Error,
+    T: Runtime,
+{
+    type Output = (Result<R, SituwaitionError<E>>, WaitReport);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut inner = self.project().inner;
+        match inner.as_mut().poll(cx) {
+            Poll::Ready(result) => {
+                let report = inner.project().observer.report().unwrap_or_default();
+                Poll::Ready((result, report))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
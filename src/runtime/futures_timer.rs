@@ -0,0 +1,121 @@
+#![cfg(feature = "futures-timer")]
+
+use std::{
+    error::Error,
+    future::Future,
+    time::{Duration, Instant},
+};
+
+use futures_timer::Delay;
+
+use crate::SituwaitionError;
+
+use super::{AsyncRuntime, AsyncWaiter, Runtime};
+
+/// [`Runtime`] that does not depend on any executor, backed by [futures-timer](https://docs.rs/futures-timer)
+///
+/// Timers are driven by a helper thread, so situwaitions run under any executor
+/// (ex. `futures::executor::block_on`), at the cost of that thread.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures-timer")))]
+pub struct FuturesTimerRuntime;
+
+impl Runtime for FuturesTimerRuntime {
+    type Sleep = Delay;
+
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        Delay::new(duration)
+    }
+}
+
+/// Wait for a given function to resolve with a given result, on any executor.
+///
+/// Returning a [Result::Ok] will end waiting, and [Result::Err]s will be ignored.
+/// The function produced by the factory will be retried up until the default timeout (see [SituwaitionOpts][crate::SituwaitionOpts])
+#[cfg_attr(docsrs, doc(cfg(feature = "futures-timer")))]
+pub async fn wait_for<R, E, F, G>(factory: F) -> Result<R, SituwaitionError<E>>
+where
+    R: Send + Sync + 'static,
+    E: Error + Send + Sync + 'static,
+    F: Fn() -> G + Send,
+    G: Future<Output = Result<R, E>> + Send,
+{
+    AsyncWaiter::from_factory(factory)
+        .exec_on(AsyncRuntime::FuturesTimer)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::IntoFuture, io::ErrorKind};
+
+    use futures_executor::block_on;
+
+    use super::*;
+
+    #[test]
+    fn test_unit_futures_timer_wait_for_fn() {
+        assert!(
+            matches!(
+                block_on(wait_for(|| async { Ok::<bool, std::io::Error>(true) })),
+                Ok(true)
+            ),
+            "wait_for_fn with a simple fn is true"
+        );
+    }
+
+    #[test]
+    fn test_unit_futures_timer_exec_fail() {
+        let start = Instant::now();
+        let result = block_on(
+            AsyncWaiter::with_timeout(
+                || async {
+                    Err::<(), std::io::Error>(std::io::Error::new(ErrorKind::Other, "test"))
+                },
+                Duration::from_millis(500),
+            )
+            .expect("failed to create")
+            .with_runtime(FuturesTimerRuntime)
+            .into_future(),
+        );
+        assert!(
+            matches!(
+                result,
+                Err(SituwaitionError::TimeoutError(std::io::Error { .. }, _))
+            ),
+            "always erroring check fails"
+        );
+        assert!(
+            Instant::now() - start >= Duration::from_millis(500),
+            "failing check waited until after timeout"
+        );
+    }
+
+    #[test]
+    fn test_unit_futures_timer_with_long_check() {
+        let start = Instant::now();
+        let result = block_on(
+            AsyncWaiter::with_timeout(
+                || async {
+                    Delay::new(Duration::from_millis(500)).await;
+                    Ok::<bool, std::io::Error>(true)
+                },
+                Duration::from_millis(250),
+            )
+            .expect("failed to create")
+            .exec_on(AsyncRuntime::FuturesTimer),
+        );
+        assert!(
+            matches!(result, Err(SituwaitionError::CheckTimeoutError(_))),
+            "check that finishes in 500ms times out in 250ms as configured"
+        );
+        assert!(
+            Instant::now() - start < Duration::from_millis(500),
+            "timed out before the check completed"
+        );
+    }
+}
@@ -0,0 +1,277 @@
+//! Waiting on checks that cannot be sent between threads
+//!
+//! [`AsyncWaiter`][super::AsyncWaiter] requires checks (and their results) to be `Send`, so that the situwaition
+//! can run on multi-threaded executors. [`LocalAsyncWaiter`] drops those bounds, for checks that use `Rc`, `RefCell`
+//! or other non-`Send` clients on single-threaded executors (ex. inside a tokio `LocalSet`).
+//!
+//! Scheduling and timeouts work exactly as they do for [`AsyncWaiter`][super::AsyncWaiter].
+
+use std::{error::Error, future::Future, future::IntoFuture, time::Duration};
+
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+))]
+use super::{AsyncRuntime, DetectedRuntime};
+use super::{DefaultRuntime, Runtime, WaitFuture, WaitReportFuture};
+use crate::{
+    builder::WaiterBuilder, cancel::CancellationToken, SituwaitionBase, SituwaitionError,
+    SituwaitionHooks, SituwaitionOpts, WaiterCreationError,
+};
+
+/// Waiter that runs an async check (produced by a factory) until it succeeds or times out,
+/// without requiring the check to be `Send`
+pub struct LocalAsyncWaiter<F, A, R, E, Rt = DefaultRuntime>
+where
+    F: Future<Output = Result<R, E>>,
+    A: Fn() -> F,
+    E: Error,
+{
+    /// Options for the situwaition
+    pub opts: SituwaitionOpts,
+
+    /// The async task that should be used to check completion
+    pub factory: A,
+
+    /// The runtime that will execute the situwaition
+    pub runtime: Rt,
+}
+
+impl<F, A, R, E, Rt> SituwaitionBase for LocalAsyncWaiter<F, A, R, E, Rt>
+where
+    F: Future<Output = Result<R, E>>,
+    A: Fn() -> F,
+    E: Error,
+{
+    type Result = R;
+    type Error = E;
+
+    fn options(&self) -> &SituwaitionOpts {
+        &self.opts
+    }
+
+    fn set_options(
+        &mut self,
+        update_fn: impl Fn(&SituwaitionOpts) -> SituwaitionOpts,
+    ) -> Result<(), SituwaitionError<()>> {
+        self.opts = update_fn(&self.opts);
+        Ok(())
+    }
+}
+
+impl<F, A, R, E> LocalAsyncWaiter<F, A, R, E>
+where
+    F: Future<Output = Result<R, E>>,
+    A: Fn() -> F,
+    E: Error,
+{
+    /// Convert an existing async function factory into a LocalAsyncWaiter
+    pub fn from_factory(factory: A) -> LocalAsyncWaiter<F, A, R, E> {
+        Self::with_opts(factory, SituwaitionOpts::default())
+    }
+
+    /// Create a LocalAsyncWaiter with options fully specified
+    pub fn with_opts(factory: A, opts: SituwaitionOpts) -> LocalAsyncWaiter<F, A, R, E> {
+        LocalAsyncWaiter {
+            opts,
+            factory,
+            runtime: DefaultRuntime::default(),
+        }
+    }
+
+    /// Start a validating builder for a LocalAsyncWaiter (see [`crate::builder`])
+    pub fn builder(factory: A) -> WaiterBuilder<A, LocalAsyncWaiter<F, A, R, E>> {
+        WaiterBuilder::new(factory, Self::with_opts)
+    }
+
+    /// Create a LocalAsyncWaiter with only timeout customized
+    pub fn with_timeout(
+        factory: A,
+        timeout: Duration,
+    ) -> Result<LocalAsyncWaiter<F, A, R, E>, WaiterCreationError> {
+        Self::builder(factory).timeout(timeout).build()
+    }
+
+    /// Create a LocalAsyncWaiter with only check interval customized
+    pub fn with_check_interval(
+        factory: A,
+        check_interval: Duration,
+    ) -> Result<LocalAsyncWaiter<F, A, R, E>, WaiterCreationError> {
+        Self::builder(factory)
+            .check_interval(check_interval)
+            .build()
+    }
+}
+
+impl<F, A, R, E, Rt> LocalAsyncWaiter<F, A, R, E, Rt>
+where
+    F: Future<Output = Result<R, E>>,
+    A: Fn() -> F,
+    E: Error,
+{
+    /// Set the name of the situwaition (see [`SituwaitionOpts::name`])
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.opts.name = Some(name.into());
+        self
+    }
+
+    /// Replace the lifecycle hooks that will be run during the situwaition
+    pub fn with_hooks(mut self, hooks: SituwaitionHooks) -> Self {
+        self.opts.hooks = hooks;
+        self
+    }
+
+    /// Stop the situwaition when the given token is cancelled (see [`crate::cancel`])
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.opts.cancellation = Some(token);
+        self
+    }
+
+    /// Use a different runtime to execute the situwaition
+    pub fn with_runtime<Rt2: Runtime>(self, runtime: Rt2) -> LocalAsyncWaiter<F, A, R, E, Rt2> {
+        LocalAsyncWaiter {
+            opts: self.opts,
+            factory: self.factory,
+            runtime,
+        }
+    }
+}
+
+impl<F, A, R, E, Rt> LocalAsyncWaiter<F, A, R, E, Rt>
+where
+    F: Future<Output = Result<R, E>>,
+    A: Fn() -> F,
+    E: Error,
+    Rt: Runtime,
+{
+    /// Execute the situwaition on the waiter's runtime, and wait until it resolves
+    /// or fails with a timeout
+    pub fn exec(&mut self) -> WaitFuture<&mut A, F, E, &Rt> {
+        WaitFuture::new(&mut self.factory, &self.opts, &self.runtime, false)
+    }
+
+    /// Execute the situwaition like [`LocalAsyncWaiter::exec`], additionally
+    /// producing a [`WaitReport`][crate::WaitReport] of the attempts that were made
+    pub fn exec_with_report(&mut self) -> WaitReportFuture<&mut A, F, E, &Rt> {
+        WaitReportFuture::new(&mut self.factory, &self.opts, &self.runtime)
+    }
+}
+
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+))]
+impl<F, A, R, E> LocalAsyncWaiter<F, A, R, E, DetectedRuntime>
+where
+    F: Future<Output = Result<R, E>>,
+    A: Fn() -> F,
+    E: Error,
+{
+    /// Execute the situwaition on the given runtime
+    pub fn exec_on(&mut self, runtime: AsyncRuntime) -> WaitFuture<&mut A, F, E, AsyncRuntime> {
+        WaitFuture::new(&mut self.factory, &self.opts, runtime, false)
+    }
+
+    /// Execute the situwaition on the given runtime, additionally
+    /// producing a [`WaitReport`][crate::WaitReport] of the attempts that were made
+    pub fn exec_with_report_on(
+        &mut self,
+        runtime: AsyncRuntime,
+    ) -> WaitReportFuture<&mut A, F, E, AsyncRuntime> {
+        WaitReportFuture::new(&mut self.factory, &self.opts, runtime)
+    }
+}
+
+/// Awaiting a [`LocalAsyncWaiter`] executes it on its runtime (see [`LocalAsyncWaiter::exec`])
+impl<F, A, R, E, Rt> IntoFuture for LocalAsyncWaiter<F, A, R, E, Rt>
+where
+    F: Future<Output = Result<R, E>>,
+    A: Fn() -> F,
+    E: Error,
+    Rt: Runtime,
+{
+    type Output = Result<R, SituwaitionError<E>>;
+    type IntoFuture = WaitFuture<A, F, E, Rt>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        WaitFuture::new(self.factory, &self.opts, self.runtime, false)
+    }
+}
+
+/// Wait for a given function to resolve with a given result, without requiring it to be `Send`.
+///
+/// Returning a [Result::Ok] will end waiting, and [Result::Err]s will be ignored.
+/// The function produced by the factory will be retried up until the default timeout (see [SituwaitionOpts])
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+))]
+pub async fn wait_for<R, E, F, G>(factory: F) -> Result<R, SituwaitionError<E>>
+where
+    E: Error,
+    F: Fn() -> G,
+    G: Future<Output = Result<R, E>>,
+{
+    LocalAsyncWaiter::from_factory(factory).await
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+    use std::{cell::RefCell, io::ErrorKind, rc::Rc};
+
+    use tokio::task::LocalSet;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unit_local_rc_check() {
+        let count = Rc::new(RefCell::new(0));
+        let result = LocalSet::new()
+            .run_until(
+                LocalAsyncWaiter::with_check_interval(
+                    || {
+                        let count = count.clone();
+                        async move {
+                            *count.borrow_mut() += 1;
+                            match *count.borrow() {
+                                3 => Ok(Rc::new("ready")),
+                                _ => Err(std::io::Error::new(ErrorKind::Other, "not ready")),
+                            }
+                        }
+                    },
+                    Duration::from_millis(10),
+                )
+                .expect("failed to create")
+                .exec_on(AsyncRuntime::Tokio),
+            )
+            .await
+            .map(|v| *v);
+
+        assert!(matches!(result, Ok("ready")), "non-Send check passed");
+        assert_eq!(*count.borrow(), 3, "check ran until it passed");
+    }
+
+    #[tokio::test]
+    async fn test_unit_local_wait_for_timeout() {
+        let result = LocalAsyncWaiter::builder(|| async {
+            Err::<Rc<()>, _>(std::io::Error::new(ErrorKind::Other, "not ready"))
+        })
+        .timeout(Duration::from_millis(100))
+        .check_interval(Duration::from_millis(10))
+        .build()
+        .expect("failed to create")
+        .with_runtime(crate::runtime::tokio::TokioRuntime)
+        .await;
+
+        assert!(
+            matches!(result, Err(SituwaitionError::TimeoutError(..))),
+            "failing non-Send check times out"
+        );
+    }
+}
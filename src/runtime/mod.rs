@@ -1,22 +1,431 @@
-use std::{error::Error, future::Future, time::Duration};
+//! Executing situwaitions asynchronously
+//!
+//! [`AsyncWaiter`]s are executed by a [`Runtime`], which provides the clock and timers.
+//! Implementations are provided for tokio, async-std and smol, as well as an executor-agnostic
+//! one backed by futures-timer (with the corresponding features),
+//! and other executors can be supported by implementing [`Runtime`]:
+//!
+//! ```
+//! use std::time::{Duration, Instant};
+//!
+//! use situwaition::runtime::{AsyncWaiter, Runtime};
+//!
+//! /// Runtime for an in-house executor
+//! #[derive(Debug, Clone, Copy)]
+//! struct InHouse;
+//!
+//! impl Runtime for InHouse {
+//!     type Sleep = std::future::Ready<()>; // ex. in_house::time::Sleep
+//!
+//!     fn now(&self) -> Instant {
+//!         Instant::now()
+//!     }
+//!
+//!     fn sleep(&self, duration: Duration) -> Self::Sleep {
+//!         std::future::ready(()) // ex. in_house::time::sleep(duration)
+//!     }
+//! }
+//!
+//! let waiter = AsyncWaiter::from_factory(|| async { Ok::<_, std::io::Error>(true) }).with_runtime(InHouse);
+//! ```
 
-use derive_builder::Builder;
+use std::{error::Error, future::Future, future::IntoFuture, time::Duration, time::Instant};
+
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+))]
+use std::{
+    pin::Pin,
+    sync::OnceLock,
+    task::{Context, Poll},
+};
+
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+))]
+use pin_project_lite::pin_project;
 
 use crate::{
-    SituwaitionBase, SituwaitionError, SituwaitionOpts, WaiterCreationError,
-    DEFAULT_SITUWAITION_CHECK_INTERVAL_MS, DEFAULT_SITUWAITION_TIMEOUT_MS,
+    builder::WaiterBuilder, cancel::CancellationToken, SituwaitionBase, SituwaitionError,
+    SituwaitionHooks, SituwaitionOpts, WaiterCreationError,
 };
 
 #[cfg(feature = "async-std")]
 #[cfg_attr(docsrs, doc(cfg(feature = "async-std")))]
 pub mod async_std;
 
+mod future;
+pub use future::{Elapsed, Timeout, WaitFuture, WaitReportFuture};
+
+pub mod local;
+pub use local::LocalAsyncWaiter;
+
+#[cfg(feature = "futures-timer")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures-timer")))]
+pub mod futures_timer;
+
+#[cfg(feature = "smol")]
+#[cfg_attr(docsrs, doc(cfg(feature = "smol")))]
+pub mod smol;
+
 #[cfg(feature = "tokio")]
 #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
 pub mod tokio;
 
-#[derive(Builder)]
-pub struct AsyncWaiter<F, A, R, E>
+/// Source of time for executing [`AsyncWaiter`]s
+///
+/// Implementations should use the clock of the executor they are built for (ex. so that
+/// tokio's paused time is respected), and must produce sleeps that work on that executor.
+pub trait Runtime {
+    /// Future produced by [`Runtime::sleep`]
+    type Sleep: Future<Output = ()>;
+
+    /// The current time, according to the runtime
+    fn now(&self) -> Instant;
+
+    /// Produce a future that completes after the given duration
+    fn sleep(&self, duration: Duration) -> Self::Sleep;
+
+    /// Run a future, failing with [`Elapsed`] if it does not complete within the given duration
+    fn timeout<F: Future>(&self, duration: Duration, future: F) -> Timeout<F, Self::Sleep> {
+        Timeout::new(future, self.sleep(duration))
+    }
+}
+
+impl<Rt: Runtime + ?Sized> Runtime for &Rt {
+    type Sleep = Rt::Sleep;
+
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        (**self).sleep(duration)
+    }
+}
+
+/// The runtime used by [`AsyncWaiter`]s that were not given one with [`AsyncWaiter::with_runtime`]
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+))]
+pub type DefaultRuntime = DetectedRuntime;
+
+/// The runtime used by [`AsyncWaiter`]s that were not given one with [`AsyncWaiter::with_runtime`]
+#[cfg(not(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+)))]
+pub type DefaultRuntime = NoRuntime;
+
+/// Placeholder runtime, used when no runtime features are enabled.
+///
+/// [`AsyncWaiter`]s cannot be executed until a [`Runtime`] is provided with [`AsyncWaiter::with_runtime`].
+#[cfg(not(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+)))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NoRuntime;
+
+/// Async runtimes that an [`AsyncWaiter`] can be executed on
+///
+/// When more than one runtime feature is enabled (ex. due to feature unification in a workspace),
+/// [`AsyncWaiter`]s pick a runtime with [`AsyncRuntime::detect`] when they are first polled (see [`DetectedRuntime`]),
+/// and [`AsyncWaiter::exec_on`] can be used to choose one explicitly.
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(
+        feature = "async-std",
+        feature = "tokio",
+        feature = "smol",
+        feature = "futures-timer"
+    )))
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsyncRuntime {
+    /// The tokio runtime (see [`TokioAsyncSituwaition`][crate::TokioAsyncSituwaition])
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    Tokio,
+
+    /// The async-std runtime (see [`AsyncStdAsyncSituwaition`][crate::AsyncStdAsyncSituwaition])
+    #[cfg(feature = "async-std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async-std")))]
+    AsyncStd,
+
+    /// The smol runtime (see [`SmolAsyncSituwaition`][crate::SmolAsyncSituwaition])
+    #[cfg(feature = "smol")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "smol")))]
+    Smol,
+
+    /// Any executor, with timers driven by futures-timer (see [`FuturesTimerRuntime`][futures_timer::FuturesTimerRuntime])
+    #[cfg(feature = "futures-timer")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "futures-timer")))]
+    FuturesTimer,
+}
+
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+))]
+impl AsyncRuntime {
+    /// Pick the runtime to use when none was specified.
+    ///
+    /// If only one runtime feature is enabled, that runtime is used. Otherwise tokio is used
+    /// when called from within a tokio runtime, and async-std, smol or futures-timer otherwise
+    /// (in that order of preference).
+    ///
+    /// async-std and smol cannot be told apart, but since both use async-io for timers,
+    /// either works when driven by the other.
+    pub fn detect() -> AsyncRuntime {
+        #[cfg(feature = "tokio")]
+        if ::tokio::runtime::Handle::try_current().is_ok() {
+            return AsyncRuntime::Tokio;
+        }
+
+        #[cfg(feature = "async-std")]
+        return AsyncRuntime::AsyncStd;
+
+        #[cfg(all(feature = "smol", not(feature = "async-std")))]
+        return AsyncRuntime::Smol;
+
+        #[cfg(all(
+            feature = "futures-timer",
+            not(any(feature = "async-std", feature = "smol"))
+        ))]
+        return AsyncRuntime::FuturesTimer;
+
+        #[cfg(not(any(feature = "async-std", feature = "smol", feature = "futures-timer")))]
+        return AsyncRuntime::Tokio;
+    }
+}
+
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+))]
+impl Default for AsyncRuntime {
+    fn default() -> Self {
+        AsyncRuntime::detect()
+    }
+}
+
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+))]
+impl Runtime for AsyncRuntime {
+    type Sleep = RuntimeSleep;
+
+    fn now(&self) -> Instant {
+        match self {
+            #[cfg(feature = "tokio")]
+            AsyncRuntime::Tokio => tokio::TokioRuntime.now(),
+            #[cfg(feature = "async-std")]
+            AsyncRuntime::AsyncStd => async_std::AsyncStdRuntime.now(),
+            #[cfg(feature = "smol")]
+            AsyncRuntime::Smol => smol::SmolRuntime.now(),
+            #[cfg(feature = "futures-timer")]
+            AsyncRuntime::FuturesTimer => futures_timer::FuturesTimerRuntime.now(),
+        }
+    }
+
+    fn sleep(&self, duration: Duration) -> RuntimeSleep {
+        match self {
+            #[cfg(feature = "tokio")]
+            AsyncRuntime::Tokio => RuntimeSleep::Tokio {
+                sleep: tokio::TokioRuntime.sleep(duration),
+            },
+            #[cfg(feature = "async-std")]
+            AsyncRuntime::AsyncStd => RuntimeSleep::AsyncStd {
+                sleep: async_std::AsyncStdRuntime.sleep(duration),
+            },
+            #[cfg(feature = "smol")]
+            AsyncRuntime::Smol => RuntimeSleep::Smol {
+                sleep: smol::SmolRuntime.sleep(duration),
+            },
+            #[cfg(feature = "futures-timer")]
+            AsyncRuntime::FuturesTimer => RuntimeSleep::FuturesTimer {
+                sleep: futures_timer::FuturesTimerRuntime.sleep(duration),
+            },
+        }
+    }
+}
+
+/// Runtime that is picked with [`AsyncRuntime::detect`] the first time it is used
+/// (ex. when a situwaition is first polled), rather than when the waiter is created.
+///
+/// Once detected, the runtime is kept for the life of the waiter.
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+))]
+#[derive(Debug, Clone, Default)]
+pub struct DetectedRuntime {
+    detected: OnceLock<AsyncRuntime>,
+}
+
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+))]
+impl DetectedRuntime {
+    /// The runtime that was detected, if the runtime has been used yet
+    pub fn get(&self) -> Option<AsyncRuntime> {
+        self.detected.get().copied()
+    }
+
+    fn runtime(&self) -> AsyncRuntime {
+        *self.detected.get_or_init(AsyncRuntime::detect)
+    }
+}
+
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+))]
+impl Runtime for DetectedRuntime {
+    type Sleep = RuntimeSleep;
+
+    fn now(&self) -> Instant {
+        self.runtime().now()
+    }
+
+    fn sleep(&self, duration: Duration) -> RuntimeSleep {
+        self.runtime().sleep(duration)
+    }
+}
+
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+))]
+pin_project! {
+    /// Sleep on one of the supported runtimes
+    ///
+    /// Every variant exists regardless of features, but those of disabled runtimes hold [`Disabled`]
+    /// (and so can never be constructed).
+    #[doc(hidden)]
+    #[project = RuntimeSleepProj]
+    pub enum RuntimeSleep {
+        Tokio { #[pin] sleep: TokioSleep },
+        AsyncStd { #[pin] sleep: AsyncStdSleep },
+        Smol { #[pin] sleep: SmolSleep },
+        FuturesTimer { #[pin] sleep: FuturesTimerSleep },
+    }
+}
+
+/// Stands in for the sleep of a runtime whose feature is disabled
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+))]
+#[doc(hidden)]
+#[derive(Debug)]
+pub enum Disabled {}
+
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+))]
+impl Future for Disabled {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        match *self {}
+    }
+}
+
+#[cfg(feature = "tokio")]
+type TokioSleep = <tokio::TokioRuntime as Runtime>::Sleep;
+#[cfg(all(
+    not(feature = "tokio"),
+    any(feature = "async-std", feature = "smol", feature = "futures-timer")
+))]
+type TokioSleep = Disabled;
+
+#[cfg(feature = "async-std")]
+type AsyncStdSleep = <async_std::AsyncStdRuntime as Runtime>::Sleep;
+#[cfg(all(
+    not(feature = "async-std"),
+    any(feature = "tokio", feature = "smol", feature = "futures-timer")
+))]
+type AsyncStdSleep = Disabled;
+
+#[cfg(feature = "smol")]
+type SmolSleep = <smol::SmolRuntime as Runtime>::Sleep;
+#[cfg(all(
+    not(feature = "smol"),
+    any(feature = "tokio", feature = "async-std", feature = "futures-timer")
+))]
+type SmolSleep = Disabled;
+
+#[cfg(feature = "futures-timer")]
+type FuturesTimerSleep = <futures_timer::FuturesTimerRuntime as Runtime>::Sleep;
+#[cfg(all(
+    not(feature = "futures-timer"),
+    any(feature = "tokio", feature = "async-std", feature = "smol")
+))]
+type FuturesTimerSleep = Disabled;
+
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+))]
+impl Future for RuntimeSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        match self.project() {
+            RuntimeSleepProj::Tokio { sleep } => sleep.poll(cx),
+            RuntimeSleepProj::AsyncStd { sleep } => sleep.poll(cx),
+            RuntimeSleepProj::Smol { sleep } => sleep.poll(cx),
+            RuntimeSleepProj::FuturesTimer { sleep } => sleep.poll(cx),
+        }
+    }
+}
+
+/// Waiter that runs an async check (produced by a factory) until it succeeds or times out
+pub struct AsyncWaiter<F, A, R, E, Rt = DefaultRuntime>
 where
     F: Future<Output = Result<R, E>> + Send,
     A: Fn() -> F + Send,
@@ -28,9 +437,12 @@ where
 
     /// The async task that should be used to check completion
     pub factory: A,
+
+    /// The runtime that will execute the situwaition
+    pub runtime: Rt,
 }
 
-impl<F, A, R, E> SituwaitionBase for AsyncWaiter<F, A, R, E>
+impl<F, A, R, E, Rt> SituwaitionBase for AsyncWaiter<F, A, R, E, Rt>
 where
     F: Future<Output = Result<R, E>> + Send,
     A: Fn() -> F + Send,
@@ -63,55 +475,429 @@ where
     /// Convert an existing async function factory into an AsyncWaiter
     #[allow(dead_code)]
     pub fn from_factory(factory: A) -> AsyncWaiter<F, A, R, E> {
-        AsyncWaiter {
-            opts: SituwaitionOpts::default(),
-            factory,
-        }
+        Self::with_opts(factory, SituwaitionOpts::default())
     }
 
     /// Create a sync executor with options fully specified
     #[allow(dead_code)]
     pub fn with_opts(factory: A, opts: SituwaitionOpts) -> AsyncWaiter<F, A, R, E> {
-        AsyncWaiter { opts, factory }
+        AsyncWaiter {
+            opts,
+            factory,
+            runtime: DefaultRuntime::default(),
+        }
     }
 
-    /// Create a SyncExecutor with only timeout customized
+    /// Start a validating builder for an AsyncWaiter (see [`crate::builder`])
+    pub fn builder(factory: A) -> WaiterBuilder<A, AsyncWaiter<F, A, R, E>> {
+        WaiterBuilder::new(factory, Self::with_opts)
+    }
+
+    /// Create an AsyncWaiter with only timeout customized
     #[allow(dead_code)]
     pub fn with_timeout(
         factory: A,
         timeout: Duration,
     ) -> Result<AsyncWaiter<F, A, R, E>, WaiterCreationError> {
-        if timeout < Duration::from_millis(DEFAULT_SITUWAITION_CHECK_INTERVAL_MS) {
-            return Err(WaiterCreationError::InvalidTimeout(
-                format!("supplied timeout ({}ms) is shorter the default timeout ({DEFAULT_SITUWAITION_CHECK_INTERVAL_MS}ms)", timeout.as_millis())
-            ));
-        }
-        Ok(Self::with_opts(
-            factory,
-            SituwaitionOpts {
-                timeout,
-                ..SituwaitionOpts::default()
-            },
-        ))
+        Self::builder(factory).timeout(timeout).build()
     }
 
-    /// Create a SyncExecutor with only check interval customized
+    /// Create an AsyncWaiter with only check interval customized
     #[allow(dead_code)]
     pub fn with_check_interval(
         factory: A,
         check_interval: Duration,
     ) -> Result<AsyncWaiter<F, A, R, E>, WaiterCreationError> {
-        if check_interval > Duration::from_millis(DEFAULT_SITUWAITION_TIMEOUT_MS) {
-            return Err(WaiterCreationError::InvalidTimeout(
-                format!("supplied check interval ({}ms) is larger than the default timeout ({DEFAULT_SITUWAITION_TIMEOUT_MS}ms)", check_interval.as_millis())
-            ));
+        Self::builder(factory)
+            .check_interval(check_interval)
+            .build()
+    }
+}
+
+impl<F, A, R, E, Rt> AsyncWaiter<F, A, R, E, Rt>
+where
+    F: Future<Output = Result<R, E>> + Send,
+    A: Fn() -> F + Send,
+    R: Send + Sync,
+    E: Error + Send + Sync,
+{
+    /// Set the name of the situwaition (see [`SituwaitionOpts::name`])
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.opts.name = Some(name.into());
+        self
+    }
+
+    /// Replace the lifecycle hooks that will be run during the situwaition
+    pub fn with_hooks(mut self, hooks: SituwaitionHooks) -> Self {
+        self.opts.hooks = hooks;
+        self
+    }
+
+    /// Stop the situwaition when the given token is cancelled (see [`crate::cancel`])
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.opts.cancellation = Some(token);
+        self
+    }
+
+    /// Use a different runtime to execute the situwaition
+    pub fn with_runtime<Rt2: Runtime>(self, runtime: Rt2) -> AsyncWaiter<F, A, R, E, Rt2> {
+        AsyncWaiter {
+            opts: self.opts,
+            factory: self.factory,
+            runtime,
         }
-        Ok(Self::with_opts(
-            factory,
-            SituwaitionOpts {
-                check_interval,
-                ..SituwaitionOpts::default()
-            },
-        ))
+    }
+}
+
+impl<F, A, R, E, Rt> AsyncWaiter<F, A, R, E, Rt>
+where
+    F: Future<Output = Result<R, E>> + Send,
+    A: Fn() -> F + Send,
+    R: Send + Sync,
+    E: Error + Send + Sync,
+    Rt: Runtime,
+{
+    /// Execute the situwaition on the waiter's runtime, and wait until it resolves
+    /// or fails with a timeout
+    pub fn exec(&mut self) -> WaitFuture<&mut A, F, E, &Rt> {
+        WaitFuture::new(&mut self.factory, &self.opts, &self.runtime, false)
+    }
+
+    /// Execute the situwaition like [`AsyncWaiter::exec`], additionally
+    /// producing a [`WaitReport`][crate::WaitReport] of the attempts that were made
+    pub fn exec_with_report(&mut self) -> WaitReportFuture<&mut A, F, E, &Rt> {
+        WaitReportFuture::new(&mut self.factory, &self.opts, &self.runtime)
+    }
+}
+
+#[cfg(any(
+    feature = "tokio",
+    feature = "async-std",
+    feature = "smol",
+    feature = "futures-timer"
+))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(
+        feature = "async-std",
+        feature = "tokio",
+        feature = "smol",
+        feature = "futures-timer"
+    )))
+)]
+impl<F, A, R, E> AsyncWaiter<F, A, R, E, DetectedRuntime>
+where
+    F: Future<Output = Result<R, E>> + Send,
+    A: Fn() -> F + Send,
+    R: Send + Sync,
+    E: Error + Send + Sync,
+{
+    /// Execute the situwaition on the given runtime
+    pub fn exec_on(&mut self, runtime: AsyncRuntime) -> WaitFuture<&mut A, F, E, AsyncRuntime> {
+        WaitFuture::new(&mut self.factory, &self.opts, runtime, false)
+    }
+
+    /// Execute the situwaition on the given runtime, additionally
+    /// producing a [`WaitReport`][crate::WaitReport] of the attempts that were made
+    pub fn exec_with_report_on(
+        &mut self,
+        runtime: AsyncRuntime,
+    ) -> WaitReportFuture<&mut A, F, E, AsyncRuntime> {
+        WaitReportFuture::new(&mut self.factory, &self.opts, runtime)
+    }
+}
+
+/// Awaiting an [`AsyncWaiter`] executes it on its runtime (see [`AsyncWaiter::exec`])
+impl<F, A, R, E, Rt> IntoFuture for AsyncWaiter<F, A, R, E, Rt>
+where
+    F: Future<Output = Result<R, E>> + Send,
+    A: Fn() -> F + Send,
+    R: Send + Sync,
+    E: Error + Send + Sync,
+    Rt: Runtime,
+{
+    type Output = Result<R, SituwaitionError<E>>;
+    type IntoFuture = WaitFuture<A, F, E, Rt>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        WaitFuture::new(self.factory, &self.opts, self.runtime, false)
+    }
+}
+
+/// Builder for [`AsyncWaiter`]s (on the default runtime)
+///
+/// See also [`AsyncWaiter::builder`], which validates options as they are set.
+pub struct AsyncWaiterBuilder<F, A, R, E>
+where
+    F: Future<Output = Result<R, E>> + Send,
+    A: Fn() -> F + Send,
+    R: Send + Sync,
+    E: Error + Send + Sync,
+{
+    opts: Option<SituwaitionOpts>,
+    factory: Option<A>,
+}
+
+impl<F, A, R, E> Default for AsyncWaiterBuilder<F, A, R, E>
+where
+    F: Future<Output = Result<R, E>> + Send,
+    A: Fn() -> F + Send,
+    R: Send + Sync,
+    E: Error + Send + Sync,
+{
+    fn default() -> Self {
+        AsyncWaiterBuilder {
+            opts: None,
+            factory: None,
+        }
+    }
+}
+
+impl<F, A, R, E> AsyncWaiterBuilder<F, A, R, E>
+where
+    F: Future<Output = Result<R, E>> + Send,
+    A: Fn() -> F + Send + Clone,
+    R: Send + Sync,
+    E: Error + Send + Sync,
+{
+    /// Options for the situwaition
+    pub fn opts(&mut self, opts: SituwaitionOpts) -> &mut Self {
+        self.opts = Some(opts);
+        self
+    }
+
+    /// The async task that should be used to check completion
+    pub fn factory(&mut self, factory: A) -> &mut Self {
+        self.factory = Some(factory);
+        self
+    }
+
+    /// Validate the options and build the waiter
+    pub fn build(&self) -> Result<AsyncWaiter<F, A, R, E>, WaiterCreationError> {
+        let opts = self.opts.clone().unwrap_or_default();
+        opts.validate()?;
+        let factory = self
+            .factory
+            .clone()
+            .ok_or_else(|| WaiterCreationError::MissingField("factory".into()))?;
+        Ok(AsyncWaiter::with_opts(factory, opts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        future::Future,
+        io::{Error, ErrorKind},
+        pin::Pin,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Context, Poll},
+        time::{Duration, Instant},
+    };
+
+    use super::{AsyncWaiter, Elapsed, Runtime};
+    use crate::{cancel::CancellationToken, SituwaitionError};
+
+    /// Runtime for an executor that situwaition has no feature for (borrowing tokio's timers)
+    #[derive(Debug, Clone, Copy)]
+    struct InHouse;
+
+    impl Runtime for InHouse {
+        type Sleep = ::tokio::time::Sleep;
+
+        fn now(&self) -> Instant {
+            ::tokio::time::Instant::now().into_std()
+        }
+
+        fn sleep(&self, duration: Duration) -> Self::Sleep {
+            ::tokio::time::sleep(duration)
+        }
+    }
+
+    /// Counts the values that are alive, for checking that nothing outlives a dropped situwaition
+    #[derive(Debug, Clone, Default)]
+    struct Live(Arc<AtomicUsize>);
+
+    impl Live {
+        fn count(&self) -> usize {
+            self.0.load(Ordering::SeqCst)
+        }
+
+        fn track(&self) -> Guard {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Guard(self.0.clone())
+        }
+    }
+
+    /// Value tracked by [`Live`] until it is dropped
+    struct Guard(Arc<AtomicUsize>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            self.0.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Runtime (borrowing tokio's timers) that keeps track of the timers that are alive
+    #[derive(Debug, Clone, Default)]
+    struct Tracked {
+        timers: Live,
+    }
+
+    struct TrackedSleep {
+        sleep: Pin<Box<::tokio::time::Sleep>>,
+        _guard: Guard,
+    }
+
+    impl Future for TrackedSleep {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            self.sleep.as_mut().poll(cx)
+        }
+    }
+
+    impl Runtime for Tracked {
+        type Sleep = TrackedSleep;
+
+        fn now(&self) -> Instant {
+            ::tokio::time::Instant::now().into_std()
+        }
+
+        fn sleep(&self, duration: Duration) -> Self::Sleep {
+            TrackedSleep {
+                sleep: Box::pin(::tokio::time::sleep(duration)),
+                _guard: self.timers.track(),
+            }
+        }
+    }
+
+    #[::tokio::test]
+    async fn test_unit_custom_runtime() {
+        let mut waiter =
+            AsyncWaiter::from_factory(|| async { Ok::<_, Error>(5) }).with_runtime(InHouse);
+        assert!(
+            matches!(waiter.exec().await, Ok(5)),
+            "waiter runs on a custom runtime"
+        );
+        assert!(
+            matches!(waiter.await, Ok(5)),
+            "waiter with a custom runtime can be awaited"
+        );
+    }
+
+    #[::tokio::test]
+    async fn test_unit_custom_runtime_timeout() {
+        let result = AsyncWaiter::builder(|| async {
+            Err::<(), _>(Error::new(ErrorKind::Other, "not ready"))
+        })
+        .timeout(Duration::from_millis(100))
+        .check_interval(Duration::from_millis(10))
+        .build()
+        .expect("failed to create")
+        .with_runtime(InHouse)
+        .await;
+        assert!(
+            matches!(result, Err(SituwaitionError::TimeoutError(..))),
+            "failing checks time out on a custom runtime"
+        );
+
+        let elapsed = InHouse
+            .timeout(Duration::from_millis(10), std::future::pending::<()>())
+            .await;
+        assert_eq!(elapsed, Err(Elapsed), "timeout elapses");
+    }
+
+    #[::tokio::test]
+    async fn test_unit_drop_during_check() {
+        let checks = Live::default();
+        let runtime = Tracked::default();
+        let mut waiter = AsyncWaiter::from_factory(|| {
+            let guard = checks.track();
+            async move {
+                let _guard = guard;
+                std::future::pending::<Result<(), Error>>().await
+            }
+        })
+        .with_runtime(runtime.clone());
+
+        let result = ::tokio::time::timeout(Duration::from_millis(50), waiter.exec()).await;
+        assert!(result.is_err(), "check was still running");
+        assert_eq!(checks.count(), 0, "dropping the future dropped the check");
+        assert_eq!(
+            runtime.timers.count(),
+            0,
+            "dropping the future dropped the timeout"
+        );
+    }
+
+    #[::tokio::test]
+    async fn test_unit_drop_between_attempts() {
+        let attempts = AtomicUsize::new(0);
+        let runtime = Tracked::default();
+        let mut waiter = AsyncWaiter::builder(|| {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            async { Err::<(), _>(Error::new(ErrorKind::Other, "not ready")) }
+        })
+        .timeout(Duration::from_secs(5))
+        .check_interval(Duration::from_secs(1))
+        .build()
+        .expect("failed to create")
+        .with_runtime(runtime.clone());
+
+        let result = ::tokio::time::timeout(Duration::from_millis(50), waiter.exec()).await;
+        assert!(result.is_err(), "waiter was sleeping between attempts");
+        assert_eq!(
+            runtime.timers.count(),
+            0,
+            "dropping the future dropped the sleep"
+        );
+
+        ::tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(
+            attempts.load(Ordering::SeqCst),
+            1,
+            "no attempts are made after the future is dropped"
+        );
+    }
+
+    #[::tokio::test]
+    async fn test_unit_cancellation_token() {
+        let checks = Live::default();
+        let runtime = Tracked::default();
+        let token = CancellationToken::new();
+        let mut waiter = AsyncWaiter::from_factory(|| {
+            let guard = checks.track();
+            async move {
+                let _guard = guard;
+                std::future::pending::<Result<(), Error>>().await
+            }
+        })
+        .with_cancellation(token.clone())
+        .with_runtime(runtime.clone());
+
+        let (result, _) = ::tokio::join!(waiter.exec(), async {
+            ::tokio::time::sleep(Duration::from_millis(20)).await;
+            token.cancel();
+        });
+        assert!(
+            matches!(result, Err(SituwaitionError::Cancelled)),
+            "cancelling the token stops the situwaition during a check"
+        );
+        assert_eq!(checks.count(), 0, "check was dropped on cancellation");
+        assert_eq!(
+            runtime.timers.count(),
+            0,
+            "timeout was dropped on cancellation"
+        );
+
+        let result = waiter.exec().await;
+        assert!(
+            matches!(result, Err(SituwaitionError::Cancelled)),
+            "already cancelled token stops the situwaition before any attempt"
+        );
     }
 }
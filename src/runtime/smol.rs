@@ -0,0 +1,360 @@
+#![cfg(feature = "smol")]
+
+use std::{
+    error::Error,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+
+use crate::{SituwaitionError, SmolAsyncSituwaition, WaitReport};
+
+use super::{AsyncRuntime, AsyncWaiter, Runtime};
+
+/// [`Runtime`] for smol
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(docsrs, doc(cfg(feature = "smol")))]
+pub struct SmolRuntime;
+
+/// Sleep produced by [`SmolRuntime`]
+#[derive(Debug)]
+#[cfg_attr(docsrs, doc(cfg(feature = "smol")))]
+pub struct Sleep(smol::Timer);
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        Pin::new(&mut self.0).poll(cx).map(|_| ())
+    }
+}
+
+impl Runtime for SmolRuntime {
+    type Sleep = Sleep;
+
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        Sleep(smol::Timer::after(duration))
+    }
+}
+
+#[async_trait]
+#[cfg_attr(docsrs, doc(cfg(feature = "smol")))]
+impl<F, A, R, E> SmolAsyncSituwaition for AsyncWaiter<F, A, R, E>
+where
+    F: Future<Output = Result<R, E>> + Send,
+    A: Fn() -> F + Send,
+    R: Send + Sync,
+    E: Error + Send + Sync,
+{
+    async fn exec(&mut self) -> Result<R, SituwaitionError<E>> {
+        self.exec_on(AsyncRuntime::Smol).await
+    }
+
+    async fn exec_with_report(&mut self) -> (Result<R, SituwaitionError<E>>, WaitReport) {
+        self.exec_with_report_on(AsyncRuntime::Smol).await
+    }
+}
+
+/// Wait for a given function to resolve with a given result.
+///
+/// Returning a [Result::Ok] will end waiting, and [Result::Err]s will be ignored.
+/// The function produced by the factory will be retried up until the default timeout (see [SituwaitionOpts][crate::SituwaitionOpts])
+#[allow(dead_code)]
+#[cfg_attr(docsrs, doc(cfg(feature = "smol")))]
+pub async fn wait_for<R, E, F, G>(factory: F) -> Result<R, SituwaitionError<E>>
+where
+    R: Send + Sync + 'static,
+    E: Error + Send + Sync + 'static,
+    F: Fn() -> G + Send,
+    G: Future<Output = Result<R, E>> + Send,
+{
+    AsyncWaiter::from_factory(factory)
+        .exec_on(AsyncRuntime::Smol)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::ErrorKind,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::{Duration, Instant},
+    };
+
+    use smol::Timer;
+
+    use crate::{
+        report::{AttemptOutcome, WaitOutcome},
+        SituwaitionHooks,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_unit_smol_wait_for_fn() {
+        smol::block_on(async {
+            assert!(
+                matches!(
+                    wait_for(|| async { Ok::<bool, std::io::Error>(true) }).await,
+                    Ok(true)
+                ),
+                "wait_for_fn with a simple fn is true"
+            );
+        })
+    }
+
+    #[test]
+    fn test_unit_smol_from_fn() {
+        smol::block_on(async {
+            assert!(
+                matches!(
+                    AsyncWaiter::from_factory(|| async { Ok::<bool, std::io::Error>(true) })
+                        .exec()
+                        .await,
+                    Ok(true)
+                ),
+                "wait_for_fn with a simple fn is true"
+            );
+        })
+    }
+
+    #[test]
+    fn test_unit_smol_exec_fail() {
+        smol::block_on(async {
+            assert!(matches!(
+                AsyncWaiter::with_timeout(
+                    || async {
+                        Err::<(), std::io::Error>(std::io::Error::new(ErrorKind::Other, "test"))
+                    },
+                    Duration::from_millis(500)
+                )
+                .expect("failed to create")
+                .exec()
+                .await,
+                Err(SituwaitionError::TimeoutError(std::io::Error { .. }, _)),
+            ),);
+        })
+    }
+
+    #[test]
+    fn test_unit_smol_exec_pass() {
+        smol::block_on(async {
+            assert!(
+                matches!(
+                    AsyncWaiter::with_check_interval(
+                        || async { Ok::<bool, std::io::Error>(true) },
+                        Duration::from_millis(100),
+                    )
+                    .expect("failed to create")
+                    .exec()
+                    .await,
+                    Ok(true)
+                ),
+                "always passing check passes in 100m with check interval of 100ms"
+            );
+        })
+    }
+
+    #[test]
+    fn test_unit_smol_wait_for_with_timeout() {
+        smol::block_on(async {
+            let start = Instant::now();
+
+            assert!(
+                matches!(
+                    AsyncWaiter::with_timeout(
+                        || async {
+                            Err::<(), std::io::Error>(std::io::Error::new(ErrorKind::Other, "test"))
+                        },
+                        Duration::from_millis(500),
+                    )
+                    .expect("failed to create")
+                    .exec()
+                    .await,
+                    Err(SituwaitionError::TimeoutError(std::io::Error { .. }, _)),
+                ),
+                "always erroring check fails"
+            );
+            assert!(
+                Instant::now() - start >= Duration::from_millis(500),
+                "failing check waited until after timeout"
+            );
+        })
+    }
+
+    #[test]
+    fn test_unit_smol_with_check_interval() {
+        smol::block_on(async {
+            let start = Instant::now();
+
+            assert!(
+                matches!(
+                    AsyncWaiter::with_check_interval(
+                        || async { Ok::<bool, std::io::Error>(true) },
+                        Duration::from_millis(100)
+                    )
+                    .expect("failed to create")
+                    .exec()
+                    .await,
+                    Ok(true)
+                ),
+                "always passing check passed"
+            );
+            assert!(
+                Instant::now() - start < Duration::from_millis(250),
+                "passed faster than default interval (250ms) w/ shorter interval"
+            );
+        })
+    }
+
+    #[test]
+    fn test_unit_smol_hooks() {
+        smol::block_on(async {
+            let attempts = Arc::new(AtomicUsize::new(0));
+            let successes = Arc::new(AtomicUsize::new(0));
+            let (shared_attempts, shared_successes) = (attempts.clone(), successes.clone());
+            let count = Arc::new(AtomicUsize::new(0));
+
+            let result = AsyncWaiter::with_check_interval(
+                || async {
+                    match count.fetch_add(1, Ordering::SeqCst) {
+                        2 => Ok(true),
+                        _ => Err(std::io::Error::new(ErrorKind::Other, "test")),
+                    }
+                },
+                Duration::from_millis(10),
+            )
+            .expect("failed to create")
+            .with_hooks(
+                SituwaitionHooks::new()
+                    .on_attempt(move |_| {
+                        shared_attempts.fetch_add(1, Ordering::SeqCst);
+                    })
+                    .on_success(move |info| {
+                        assert_eq!(info.attempt, 3, "success happened on the third attempt");
+                        shared_successes.fetch_add(1, Ordering::SeqCst);
+                    }),
+            )
+            .exec()
+            .await;
+
+            assert!(matches!(result, Ok(true)), "check eventually passed");
+            assert_eq!(
+                attempts.load(Ordering::SeqCst),
+                3,
+                "attempt hook ran per attempt"
+            );
+            assert_eq!(successes.load(Ordering::SeqCst), 1, "success hook ran once");
+        })
+    }
+
+    #[test]
+    fn test_unit_smol_exec_with_report() {
+        smol::block_on(async {
+            let count = AtomicUsize::new(0);
+            let (result, report) = AsyncWaiter::with_check_interval(
+                || async {
+                    match count.fetch_add(1, Ordering::SeqCst) {
+                        2 => Ok(true),
+                        _ => Err(std::io::Error::new(ErrorKind::Other, "test")),
+                    }
+                },
+                Duration::from_millis(50),
+            )
+            .expect("failed to create")
+            .exec_with_report()
+            .await;
+
+            assert!(matches!(result, Ok(true)), "check eventually passed");
+            assert_eq!(report.outcome, WaitOutcome::Succeeded);
+            assert_eq!(
+                report
+                    .attempts
+                    .iter()
+                    .map(|a| a.outcome)
+                    .collect::<Vec<_>>(),
+                vec![
+                    AttemptOutcome::Failed,
+                    AttemptOutcome::Failed,
+                    AttemptOutcome::Succeeded
+                ],
+                "every attempt was recorded"
+            );
+            assert!(
+                report.total_sleep() >= Duration::from_millis(100),
+                "intervals are counted as sleep"
+            );
+        })
+    }
+
+    #[test]
+    fn test_unit_smol_with_long_check() {
+        smol::block_on(async {
+            let start = Instant::now();
+            assert!(
+                matches!(
+                    AsyncWaiter::with_timeout(
+                        || async {
+                            Timer::after(Duration::from_millis(500)).await;
+                            Ok::<bool, std::io::Error>(true)
+                        },
+                        Duration::from_millis(250)
+                    )
+                    .expect("failed to create")
+                    .exec()
+                    .await,
+                    Err(SituwaitionError::CheckTimeoutError(_)),
+                ),
+                "check that finishes in 500ms times out in 100ms as configured"
+            );
+            assert!(
+                Instant::now() - start < Duration::from_millis(500),
+                "timed out before the check completed"
+            );
+        })
+    }
+
+    /// With tokio and smol enabled, smol is used outside of a tokio runtime
+    #[cfg(all(feature = "tokio", feature = "smol", not(feature = "async-std")))]
+    #[test]
+    fn test_unit_smol_detect_runtime() {
+        smol::block_on(async {
+            assert_eq!(
+                crate::runtime::AsyncRuntime::detect(),
+                crate::runtime::AsyncRuntime::Smol
+            );
+            assert!(
+                matches!(
+                    AsyncWaiter::from_factory(|| async { Ok::<bool, std::io::Error>(true) })
+                        .exec()
+                        .await,
+                    Ok(true)
+                ),
+                "exec is unambiguous and runs on the detected runtime"
+            );
+        })
+    }
+
+    #[test]
+    fn test_unit_smol_await_waiter() {
+        smol::block_on(async {
+            let result = AsyncWaiter::with_timeout(
+                || async { Ok::<bool, std::io::Error>(true) },
+                Duration::from_millis(500),
+            )
+            .expect("failed to create")
+            .await;
+            assert!(matches!(result, Ok(true)), "waiter can be awaited directly");
+        })
+    }
+}
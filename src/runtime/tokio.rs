@@ -1,13 +1,45 @@
+//! Waiting on the tokio runtime
+//!
+//! The tokio backend measures and sleeps using only tokio's clock ([`tokio::time::Instant`] and
+//! [`tokio::time::sleep`]), for the overall timeout, per-check timeouts, cooldowns and intervals alike.
+//! Waits therefore auto-advance in tests that run with paused time (ex. `#[tokio::test(start_paused = true)]`),
+//! taking no real time. See [`paused`] for helpers to step through attempts in such tests.
 #![cfg(feature = "tokio")]
 
-use std::{error::Error, future::Future};
+use std::{
+    error::Error,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
-use tokio::time::{sleep, timeout, Instant};
 
-use crate::{TokioAsyncSituwaition, SituwaitionError};
+use crate::{
+    handle::{AsyncWaitHandle, Progress},
+    SituwaitionError, TokioAsyncSituwaition, WaitReport,
+};
 
-use super::AsyncWaiter;
+use super::{AsyncRuntime, AsyncWaiter, Runtime, WaitFuture};
+
+/// [`Runtime`] backed by tokio's clock and timers
+///
+/// Only tokio's clock is used (never [`std::time::Instant::now`]), so that paused time is respected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub struct TokioRuntime;
+
+impl Runtime for TokioRuntime {
+    type Sleep = tokio::time::Sleep;
+
+    fn now(&self) -> Instant {
+        tokio::time::Instant::now().into_std()
+    }
+
+    fn sleep(&self, duration: Duration) -> Self::Sleep {
+        tokio::time::sleep(duration)
+    }
+}
 
 #[async_trait]
 #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
@@ -19,32 +51,44 @@ where
     E: Error + Send + Sync,
 {
     async fn exec(&mut self) -> Result<R, SituwaitionError<E>> {
-        let start = Instant::now();
-        let check_timeout = self.opts.timeout;
-        let cooldown = self.opts.check_cooldown;
-
-        loop {
-            let fut = (self.factory)();
-            match timeout(check_timeout, fut).await {
-                // Check completed in time and successfully and we can return
-                Ok(Ok(v)) => return Ok(v),
-                // Check timed out
-                Err(_) => return Err(SituwaitionError::CheckTimeoutError),
-                // Check completed in time but failed
-                Ok(Err(e)) => {
-                    if let Some(t) = cooldown {
-                        sleep(t).await;
-                    }
+        self.exec_on(AsyncRuntime::Tokio).await
+    }
 
-                    if Instant::now() - start > self.opts.timeout {
-                        return Err(SituwaitionError::TimeoutError(e));
-                    }
+    async fn exec_with_report(&mut self) -> (Result<R, SituwaitionError<E>>, WaitReport) {
+        self.exec_with_report_on(AsyncRuntime::Tokio).await
+    }
+}
 
-                    // If we got a condition failure, sleep and try again in the next loop
-                    sleep(self.opts.check_interval).await;
-                }
-            }
-        }
+impl<F, A, R, E, Rt> AsyncWaiter<F, A, R, E, Rt>
+where
+    F: Future<Output = Result<R, E>> + Send + 'static,
+    A: Fn() -> F + Send + 'static,
+    R: Send + Sync + 'static,
+    E: Error + Send + Sync + 'static,
+    Rt: Runtime + Send + 'static,
+    Rt::Sleep: Send,
+{
+    /// Run the situwaition as a tokio task, returning a handle to it
+    ///
+    /// The handle can be used to check on the situwaition without waiting, to wait for it
+    /// (optionally with a timeout), to follow its progress and to cancel it (see [`AsyncWaitHandle`]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    pub fn spawn(self) -> AsyncWaitHandle<R, E> {
+        let progress = Arc::new(Mutex::new(Progress::default()));
+        let result = Arc::new(Mutex::new(None));
+        let future = WaitFuture::new(self.factory, &self.opts, self.runtime, false)
+            .with_progress(progress.clone());
+
+        let task_result = result.clone();
+        let task = tokio::spawn(async move {
+            let outcome = future.await;
+            *task_result.lock().unwrap_or_else(|e| e.into_inner()) = Some(outcome);
+        });
+
+        AsyncWaitHandle::new(task, result, progress)
     }
 }
 
@@ -61,16 +105,261 @@ where
     F: Fn() -> G + Send,
     G: Future<Output = Result<R, E>> + Send,
 {
-    AsyncWaiter::from_factory(factory).exec().await
+    AsyncWaiter::from_factory(factory)
+        .exec_on(AsyncRuntime::Tokio)
+        .await
+}
+
+/// Helpers for stepping through attempts in tests that run with tokio's time paused
+///
+/// Track the waiter's attempts with [`paused::Attempts`], run the waiter in a spawned task, then
+/// step it along from the test. While the test waits for an attempt, paused time auto-advances
+/// straight to it (through any cooldown and interval):
+///
+/// ```
+/// # use std::{future::IntoFuture, time::Duration};
+/// # use situwaition::{runtime::{tokio::paused, AsyncWaiter}, SituwaitionOpts};
+/// # #[tokio::main(flavor = "current_thread", start_paused = true)]
+/// # async fn main() {
+/// let attempts = paused::Attempts::new();
+/// let opts = attempts.track(SituwaitionOpts {
+///     timeout: Duration::from_secs(60),
+///     check_interval: Duration::from_secs(5),
+///     ..SituwaitionOpts::default()
+/// });
+///
+/// let waiter = AsyncWaiter::with_opts(
+///     || async { Err::<(), _>(std::io::Error::new(std::io::ErrorKind::Other, "not ready")) },
+///     opts,
+/// );
+/// tokio::spawn(waiter.into_future());
+///
+/// attempts.next_attempt().await;
+/// assert_eq!(attempts.finished(), 1);
+///
+/// attempts.next_attempt().await;
+/// assert_eq!(attempts.finished(), 2);
+/// # }
+/// ```
+#[cfg(feature = "tokio-test-util")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio-test-util")))]
+pub mod paused {
+    use std::sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use tokio::sync::Notify;
+
+    use crate::{SituwaitionHooks, SituwaitionOpts};
+
+    #[derive(Debug, Default)]
+    struct Inner {
+        /// Attempts that have finished (successfully or not)
+        finished: AtomicUsize,
+
+        /// Whether the situwaition is over (no more attempts will be made), because it succeeded,
+        /// timed out, or was cancelled or dropped
+        over: AtomicBool,
+
+        notify: Notify,
+    }
+
+    impl Inner {
+        fn attempt_finished(&self) {
+            self.finished.fetch_add(1, Ordering::SeqCst);
+            self.notify.notify_waiters();
+        }
+
+        fn over(&self) {
+            self.over.store(true, Ordering::SeqCst);
+            self.notify.notify_waiters();
+        }
+    }
+
+    /// Follows the attempts of a situwaition (through its hooks), so that tests can wait for them
+    #[derive(Debug, Clone, Default)]
+    pub struct Attempts {
+        inner: Arc<Inner>,
+    }
+
+    impl Attempts {
+        /// Create a tracker that has not seen any attempts
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Options that report attempts to this tracker, in addition to running their own hooks
+        pub fn track(&self, opts: SituwaitionOpts) -> SituwaitionOpts {
+            SituwaitionOpts {
+                hooks: self.hooks(opts.hooks.clone()),
+                ..opts
+            }
+        }
+
+        /// Hooks that report attempts to this tracker, in addition to running the given hooks
+        pub fn hooks(&self, hooks: SituwaitionHooks) -> SituwaitionHooks {
+            let SituwaitionHooks {
+                on_attempt,
+                on_failure,
+                on_success,
+                on_cancel,
+                on_timeout,
+            } = hooks;
+            let (failure, success, cancel, timeout) = (
+                self.inner.clone(),
+                self.inner.clone(),
+                self.inner.clone(),
+                self.inner.clone(),
+            );
+
+            SituwaitionHooks {
+                on_attempt,
+                on_failure: Some(Arc::new(move |info, err| {
+                    if let Some(hook) = &on_failure {
+                        hook(info, err);
+                    }
+                    failure.attempt_finished();
+                })),
+                on_success: Some(Arc::new(move |info| {
+                    if let Some(hook) = &on_success {
+                        hook(info);
+                    }
+                    success.attempt_finished();
+                    success.over();
+                })),
+                on_cancel: Some(Arc::new(move |info| {
+                    if let Some(hook) = &on_cancel {
+                        hook(info);
+                    }
+                    cancel.over();
+                })),
+                on_timeout: Some(Arc::new(move |info| {
+                    let diagnostics = on_timeout.as_ref().and_then(|hook| hook(info));
+                    timeout.over();
+                    diagnostics
+                })),
+            }
+        }
+
+        /// The number of attempts that have finished so far
+        pub fn finished(&self) -> usize {
+            self.inner.finished.load(Ordering::SeqCst)
+        }
+
+        /// Wait until `attempts` attempts have finished, or the situwaition is over
+        pub async fn wait_for(&self, attempts: usize) {
+            loop {
+                // Registered before checking, so that a notification in between is not missed
+                let notified = self.inner.notify.notified();
+                if self.finished() >= attempts || self.inner.over.load(Ordering::SeqCst) {
+                    return;
+                }
+                notified.await;
+            }
+        }
+
+        /// Wait until the next attempt (after the ones that have finished so far) has finished,
+        /// or the situwaition is over
+        pub async fn next_attempt(&self) {
+            self.wait_for(self.finished() + 1).await;
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::io::ErrorKind;
-    use tokio::time::Duration;
+    use std::{
+        future::IntoFuture,
+        io::ErrorKind,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+    use tokio::time::{sleep, Duration, Instant};
+
+    use crate::{
+        report::{AttemptOutcome, WaitOutcome},
+        SituwaitionHooks,
+    };
 
     use super::*;
 
+    #[tokio::test]
+    async fn test_unit_tokio_spawn_join() {
+        let mut handle = AsyncWaiter::with_check_interval(
+            || async {
+                sleep(Duration::from_millis(50)).await;
+                Ok::<_, std::io::Error>("ready")
+            },
+            Duration::from_millis(10),
+        )
+        .expect("failed to create")
+        .spawn();
+
+        assert!(handle.try_result().is_none(), "still waiting");
+        assert!(
+            matches!(
+                handle.join_timeout(Duration::from_secs(1)).await,
+                Some(Ok("ready"))
+            ),
+            "joined the successful result"
+        );
+        assert_eq!(handle.progress().attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_unit_tokio_spawn_progress_and_cancel() {
+        let handle = AsyncWaiter::with_check_interval(
+            || async { Err::<(), _>(std::io::Error::new(ErrorKind::Other, "not ready")) },
+            Duration::from_millis(10),
+        )
+        .expect("failed to create")
+        .spawn();
+
+        sleep(Duration::from_millis(100)).await;
+        let progress = handle.progress();
+        assert!(progress.attempts > 1, "attempts are reported while waiting");
+        assert_eq!(progress.last_error.as_deref(), Some("not ready"));
+
+        handle.cancel();
+        assert!(
+            matches!(handle.join().await, Err(SituwaitionError::Cancelled)),
+            "cancelled task finishes with a cancellation error"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unit_tokio_spawn_cancel_drops_check() {
+        let check = Arc::new(());
+        let handle = AsyncWaiter::from_factory({
+            let check = check.clone();
+            move || {
+                let check = check.clone();
+                async move {
+                    let _check = check;
+                    std::future::pending::<Result<(), std::io::Error>>().await
+                }
+            }
+        })
+        .spawn();
+
+        sleep(Duration::from_millis(20)).await;
+        assert_eq!(Arc::strong_count(&check), 3, "check is in flight");
+
+        handle.cancel();
+        assert!(matches!(
+            handle.join().await,
+            Err(SituwaitionError::Cancelled)
+        ));
+        assert_eq!(
+            Arc::strong_count(&check),
+            1,
+            "aborted task dropped the check and the factory"
+        );
+    }
+
     #[tokio::test]
     async fn test_unit_tokio_wait_for_fn() {
         assert!(
@@ -107,7 +396,7 @@ mod tests {
             .expect("failed to create")
             .exec()
             .await,
-            Err(SituwaitionError::TimeoutError(std::io::Error { .. })),
+            Err(SituwaitionError::TimeoutError(std::io::Error { .. }, _)),
         ),);
     }
 
@@ -143,7 +432,7 @@ mod tests {
                 .expect("failed to create")
                 .exec()
                 .await,
-                Err(SituwaitionError::TimeoutError(std::io::Error { .. })),
+                Err(SituwaitionError::TimeoutError(std::io::Error { .. }, _)),
             ),
             "always erroring check fails"
         );
@@ -176,6 +465,82 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_unit_tokio_hooks() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let successes = Arc::new(AtomicUsize::new(0));
+        let (shared_attempts, shared_successes) = (attempts.clone(), successes.clone());
+        let count = Arc::new(AtomicUsize::new(0));
+
+        let result = AsyncWaiter::with_check_interval(
+            || async {
+                match count.fetch_add(1, Ordering::SeqCst) {
+                    2 => Ok(true),
+                    _ => Err(std::io::Error::new(ErrorKind::Other, "test")),
+                }
+            },
+            Duration::from_millis(10),
+        )
+        .expect("failed to create")
+        .with_hooks(
+            SituwaitionHooks::new()
+                .on_attempt(move |_| {
+                    shared_attempts.fetch_add(1, Ordering::SeqCst);
+                })
+                .on_success(move |info| {
+                    assert_eq!(info.attempt, 3, "success happened on the third attempt");
+                    shared_successes.fetch_add(1, Ordering::SeqCst);
+                }),
+        )
+        .exec()
+        .await;
+
+        assert!(matches!(result, Ok(true)), "check eventually passed");
+        assert_eq!(
+            attempts.load(Ordering::SeqCst),
+            3,
+            "attempt hook ran per attempt"
+        );
+        assert_eq!(successes.load(Ordering::SeqCst), 1, "success hook ran once");
+    }
+
+    #[tokio::test]
+    async fn test_unit_tokio_exec_with_report() {
+        let count = AtomicUsize::new(0);
+        let (result, report) = AsyncWaiter::with_check_interval(
+            || async {
+                match count.fetch_add(1, Ordering::SeqCst) {
+                    2 => Ok(true),
+                    _ => Err(std::io::Error::new(ErrorKind::Other, "test")),
+                }
+            },
+            Duration::from_millis(50),
+        )
+        .expect("failed to create")
+        .exec_with_report()
+        .await;
+
+        assert!(matches!(result, Ok(true)), "check eventually passed");
+        assert_eq!(report.outcome, WaitOutcome::Succeeded);
+        assert_eq!(
+            report
+                .attempts
+                .iter()
+                .map(|a| a.outcome)
+                .collect::<Vec<_>>(),
+            vec![
+                AttemptOutcome::Failed,
+                AttemptOutcome::Failed,
+                AttemptOutcome::Succeeded
+            ],
+            "every attempt was recorded"
+        );
+        assert!(
+            report.total_sleep() >= Duration::from_millis(100),
+            "intervals are counted as sleep"
+        );
+    }
+
     #[tokio::test]
     async fn test_unit_tokio_with_long_check() {
         let start = Instant::now();
@@ -191,7 +556,7 @@ mod tests {
                 .expect("failed to create")
                 .exec()
                 .await,
-                Err(SituwaitionError::CheckTimeoutError),
+                Err(SituwaitionError::CheckTimeoutError(_)),
             ),
             "check that finishes in 500ms times out in 100ms as configured"
         );
@@ -200,4 +565,238 @@ mod tests {
             "timed out before the check completed"
         );
     }
+
+    /// With both runtime features enabled, the runtime that is driving the waiter is detected
+    #[cfg(all(feature = "tokio", feature = "async-std"))]
+    #[tokio::test]
+    async fn test_unit_tokio_detect_runtime() {
+        assert_eq!(
+            crate::runtime::AsyncRuntime::detect(),
+            crate::runtime::AsyncRuntime::Tokio
+        );
+        assert!(
+            matches!(
+                AsyncWaiter::from_factory(|| async { Ok::<bool, std::io::Error>(true) })
+                    .exec()
+                    .await,
+                Ok(true)
+            ),
+            "exec is unambiguous and runs on the detected runtime"
+        );
+    }
+
+    /// The runtime is detected when the waiter is first polled, not when it is created
+    #[cfg(any(feature = "async-std", feature = "smol"))]
+    #[test]
+    fn test_unit_tokio_detect_runtime_lazily() {
+        let mut waiter = AsyncWaiter::from_factory(|| async { Ok::<bool, std::io::Error>(true) });
+        assert_eq!(
+            waiter.runtime.get(),
+            None,
+            "runtime is not detected at creation"
+        );
+
+        let result = ::tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .expect("failed to build runtime")
+            .block_on(waiter.exec());
+        assert!(matches!(result, Ok(true)));
+        assert_eq!(
+            waiter.runtime.get(),
+            Some(crate::runtime::AsyncRuntime::Tokio),
+            "runtime driving the first poll is detected"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unit_tokio_await_waiter() {
+        let result = AsyncWaiter::with_timeout(
+            || async { Ok::<bool, std::io::Error>(true) },
+            Duration::from_millis(500),
+        )
+        .expect("failed to create")
+        .await;
+        assert!(matches!(result, Ok(true)), "waiter can be awaited directly");
+    }
+
+    #[tokio::test]
+    async fn test_unit_tokio_exec_in_select() {
+        let mut waiter = AsyncWaiter::from_factory(|| async {
+            Err::<bool, _>(std::io::Error::new(ErrorKind::Other, "never ready"))
+        });
+        let exec = waiter.exec();
+        tokio::pin!(exec);
+
+        tokio::select! {
+            _ = &mut exec => panic!("situwaition should not finish before the other branch"),
+            _ = sleep(Duration::from_millis(50)) => {}
+        }
+
+        let spawned = tokio::spawn(
+            AsyncWaiter::from_factory(|| async { Ok::<bool, std::io::Error>(true) }).into_future(),
+        );
+        assert!(
+            matches!(spawned.await, Ok(Ok(true))),
+            "wait future can be spawned"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_unit_tokio_paused_timeout() {
+        let real_start = std::time::Instant::now();
+        let start = Instant::now();
+
+        let result = AsyncWaiter::builder(|| async {
+            Err::<(), _>(std::io::Error::new(ErrorKind::Other, "not ready"))
+        })
+        .timeout(Duration::from_secs(300))
+        .check_interval(Duration::from_secs(10))
+        .build()
+        .expect("failed to create")
+        .await;
+
+        assert!(
+            matches!(result, Err(SituwaitionError::TimeoutError(..))),
+            "failing check times out"
+        );
+        assert_eq!(
+            Instant::now() - start,
+            Duration::from_secs(310),
+            "overall deadline is measured in paused time"
+        );
+        assert!(
+            real_start.elapsed() < Duration::from_secs(5),
+            "paused time auto-advanced"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_unit_tokio_paused_check_timeout() {
+        let start = Instant::now();
+
+        let result = AsyncWaiter::builder(|| async {
+            sleep(Duration::from_secs(600)).await;
+            Ok::<_, std::io::Error>(())
+        })
+        .timeout(Duration::from_secs(60))
+        .check_interval(Duration::from_secs(1))
+        .build()
+        .expect("failed to create")
+        .await;
+
+        assert!(
+            matches!(result, Err(SituwaitionError::CheckTimeoutError(_))),
+            "slow check times out"
+        );
+        assert_eq!(
+            Instant::now() - start,
+            Duration::from_secs(60),
+            "per-check timeout is measured in paused time"
+        );
+    }
+
+    #[cfg(feature = "tokio-test-util")]
+    #[tokio::test(start_paused = true)]
+    async fn test_unit_tokio_paused_step_attempts() {
+        let started = Arc::new(AtomicUsize::new(0));
+        let shared_started = started.clone();
+        let attempts = paused::Attempts::new();
+        let opts = attempts.track(crate::SituwaitionOpts {
+            timeout: Duration::from_secs(30),
+            check_interval: Duration::from_secs(10),
+            check_cooldown: Some(Duration::from_secs(1)),
+            hooks: SituwaitionHooks::new().on_attempt(move |_| {
+                shared_started.fetch_add(1, Ordering::SeqCst);
+            }),
+            ..crate::SituwaitionOpts::default()
+        });
+        let waiter = AsyncWaiter::with_opts(
+            || async { Err::<(), _>(std::io::Error::new(ErrorKind::Other, "not ready")) },
+            opts,
+        );
+        let start = Instant::now();
+        let handle = tokio::spawn(waiter.into_future());
+
+        attempts.next_attempt().await;
+        assert_eq!(attempts.finished(), 1, "first attempt ran");
+        assert_eq!(Instant::now() - start, Duration::ZERO);
+
+        // Step from the middle of the cooldown and interval
+        sleep(Duration::from_secs(5)).await;
+        assert_eq!(
+            attempts.finished(),
+            1,
+            "no attempt before the interval passed"
+        );
+
+        attempts.next_attempt().await;
+        assert_eq!(attempts.finished(), 2, "stepped to the next attempt");
+        assert_eq!(
+            Instant::now() - start,
+            Duration::from_secs(11),
+            "next attempt ran after the cooldown and interval, not a fixed step"
+        );
+        assert_eq!(
+            started.load(Ordering::SeqCst),
+            2,
+            "existing hooks still run"
+        );
+
+        attempts.wait_for(usize::MAX).await;
+        assert!(
+            matches!(handle.await, Ok(Err(SituwaitionError::TimeoutError(..)))),
+            "waiter timed out"
+        );
+        assert_eq!(
+            attempts.finished(),
+            4,
+            "every attempt (at 0s, 11s, 22s and 33s) was followed"
+        );
+    }
+
+    #[cfg(feature = "tokio-test-util")]
+    #[tokio::test(start_paused = true)]
+    async fn test_unit_tokio_paused_step_cancelled() {
+        let hanging_waiter = |attempts: &paused::Attempts, token| {
+            let opts = attempts.track(crate::SituwaitionOpts {
+                timeout: Duration::from_secs(3600),
+                check_interval: Duration::from_secs(1),
+                cancellation: Some(token),
+                ..crate::SituwaitionOpts::default()
+            });
+            AsyncWaiter::with_opts(std::future::pending::<Result<(), std::io::Error>>, opts)
+        };
+
+        // Cancelled with a token while the test waits for the hanging attempt
+        let attempts = paused::Attempts::new();
+        let token = crate::cancel::CancellationToken::new();
+        let handle = tokio::spawn(hanging_waiter(&attempts, token.clone()).into_future());
+        tokio::spawn(async move {
+            sleep(Duration::from_secs(10)).await;
+            token.cancel();
+        });
+
+        tokio::time::timeout(Duration::from_secs(60), attempts.next_attempt())
+            .await
+            .expect("stepper is woken when the situwaition is cancelled");
+        assert_eq!(attempts.finished(), 0, "the attempt never finished");
+        assert!(
+            matches!(handle.await, Ok(Err(SituwaitionError::Cancelled))),
+            "waiter was cancelled"
+        );
+
+        // Dropped (with its task) while the test waits for the hanging attempt
+        let attempts = paused::Attempts::new();
+        let handle = tokio::spawn(hanging_waiter(&attempts, Default::default()).into_future());
+        tokio::spawn(async move {
+            sleep(Duration::from_secs(10)).await;
+            handle.abort();
+        });
+
+        tokio::time::timeout(Duration::from_secs(60), attempts.next_attempt())
+            .await
+            .expect("stepper is woken when the situwaition is dropped");
+        assert_eq!(attempts.finished(), 0, "the attempt never finished");
+    }
 }
@@ -0,0 +1,161 @@
+//! Process-wide scaling of timeouts
+//!
+//! Timeouts that are tuned on a developer machine are often too short for shared CI runners.
+//! Rather than changing every situwaition, a scale factor can be set for the whole process,
+//! either programmatically with [`set_timeout_scale`] or with the `SITUWAITION_TIMEOUT_SCALE`
+//! environment variable (ex. `SITUWAITION_TIMEOUT_SCALE=2.5`).
+//!
+//! The scale factor applies to the timeout of every situwaition (and so to the time a single check may take).
+//! Check intervals and cooldowns are only scaled if enabled with [`set_scale_intervals`]
+//! (or `SITUWAITION_SCALE_INTERVALS=true`).
+//!
+//! The scale factor in effect when a situwaition starts is kept on its timeout errors
+//! (see [`SituwaitionError::timeout_scale`][crate::SituwaitionError::timeout_scale]), which mention it when displayed,
+//! and recorded in its [`WaitReport`][crate::WaitReport::timeout_scale].
+
+use std::sync::{
+    atomic::{AtomicU64, AtomicU8, Ordering},
+    OnceLock,
+};
+
+use crate::SituwaitionOpts;
+
+/// Environment variable that sets the timeout scale factor
+pub const ENV_TIMEOUT_SCALE: &str = "SITUWAITION_TIMEOUT_SCALE";
+
+/// Environment variable that enables scaling check intervals and cooldowns
+pub const ENV_SCALE_INTERVALS: &str = "SITUWAITION_SCALE_INTERVALS";
+
+/// Marks a programmatic setting as absent (so that the environment is consulted)
+const UNSET: u64 = u64::MAX;
+
+/// Scale factor set via [`set_timeout_scale`] (as [`f64`] bits)
+static TIMEOUT_SCALE: AtomicU64 = AtomicU64::new(UNSET);
+
+/// Whether intervals should be scaled (0 = unset, 1 = no, 2 = yes)
+static SCALE_INTERVALS: AtomicU8 = AtomicU8::new(0);
+
+static ENV_TIMEOUT_SCALE_VALUE: OnceLock<f64> = OnceLock::new();
+static ENV_SCALE_INTERVALS_VALUE: OnceLock<bool> = OnceLock::new();
+
+/// Set the process-wide timeout scale factor, overriding `SITUWAITION_TIMEOUT_SCALE`
+///
+/// # Panics
+///
+/// Panics if the factor is not a finite, positive number.
+pub fn set_timeout_scale(factor: f64) {
+    assert!(
+        factor.is_finite() && factor > 0.0,
+        "timeout scale factor must be a finite, positive number (got {factor})"
+    );
+    TIMEOUT_SCALE.store(factor.to_bits(), Ordering::SeqCst);
+}
+
+/// Set whether check intervals and cooldowns are scaled as well, overriding `SITUWAITION_SCALE_INTERVALS`
+pub fn set_scale_intervals(enabled: bool) {
+    SCALE_INTERVALS.store(if enabled { 2 } else { 1 }, Ordering::SeqCst);
+}
+
+/// Remove programmatic settings, falling back to the environment (or no scaling)
+pub fn reset() {
+    TIMEOUT_SCALE.store(UNSET, Ordering::SeqCst);
+    SCALE_INTERVALS.store(0, Ordering::SeqCst);
+}
+
+/// The timeout scale factor currently in effect (1.0 if no scaling is configured)
+///
+/// Invalid values of `SITUWAITION_TIMEOUT_SCALE` are ignored.
+pub fn timeout_scale() -> f64 {
+    match TIMEOUT_SCALE.load(Ordering::SeqCst) {
+        UNSET => *ENV_TIMEOUT_SCALE_VALUE.get_or_init(|| {
+            std::env::var(ENV_TIMEOUT_SCALE)
+                .ok()
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|f| f.is_finite() && *f > 0.0)
+                .unwrap_or(1.0)
+        }),
+        bits => f64::from_bits(bits),
+    }
+}
+
+/// Whether check intervals and cooldowns are scaled along with timeouts
+pub fn scale_intervals() -> bool {
+    match SCALE_INTERVALS.load(Ordering::SeqCst) {
+        0 => *ENV_SCALE_INTERVALS_VALUE.get_or_init(|| {
+            std::env::var(ENV_SCALE_INTERVALS)
+                .map(|v| matches!(v.trim(), "1" | "true" | "yes"))
+                .unwrap_or(false)
+        }),
+        v => v == 2,
+    }
+}
+
+/// Note to append to descriptions of a situwaition that ran with the given scale factor
+pub(crate) fn describe(factor: f64) -> String {
+    if factor == 1.0 {
+        return String::new();
+    }
+    format!(" (timeouts scaled by {factor}x)")
+}
+
+impl SituwaitionOpts {
+    /// Options with the process-wide scale factor (see [`crate::scale`]) applied
+    pub fn scaled(&self) -> SituwaitionOpts {
+        self.scaled_with_factor().0
+    }
+
+    /// Options with the process-wide scale factor applied, along with the factor
+    pub(crate) fn scaled_with_factor(&self) -> (SituwaitionOpts, f64) {
+        let factor = timeout_scale();
+        (self.scaled_by(factor, scale_intervals()), factor)
+    }
+
+    /// Options with the given scale factor applied to the timeout (and optionally intervals)
+    pub(crate) fn scaled_by(&self, factor: f64, intervals: bool) -> SituwaitionOpts {
+        let mut opts = self.clone();
+        if factor == 1.0 {
+            return opts;
+        }
+
+        opts.timeout = opts.timeout.mul_f64(factor);
+        if intervals {
+            opts.check_interval = opts.check_interval.mul_f64(factor);
+            opts.check_cooldown = opts.check_cooldown.map(|c| c.mul_f64(factor));
+        }
+        opts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_unit_opts_scaled_by() {
+        let opts = SituwaitionOpts {
+            timeout: Duration::from_secs(2),
+            check_interval: Duration::from_millis(100),
+            check_cooldown: Some(Duration::from_millis(10)),
+            ..SituwaitionOpts::default()
+        };
+
+        let scaled = opts.scaled_by(1.5, false);
+        assert_eq!(scaled.timeout, Duration::from_secs(3), "timeout is scaled");
+        assert_eq!(
+            scaled.check_interval, opts.check_interval,
+            "interval is not scaled unless requested"
+        );
+
+        let scaled = opts.scaled_by(1.5, true);
+        assert_eq!(scaled.check_interval, Duration::from_millis(150));
+        assert_eq!(scaled.check_cooldown, Some(Duration::from_millis(15)));
+    }
+
+    #[test]
+    fn test_unit_describe_scale() {
+        assert_eq!(describe(1.0), "", "no scaling is not mentioned");
+        assert_eq!(describe(1.5), " (timeouts scaled by 1.5x)");
+    }
+}
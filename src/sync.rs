@@ -1,12 +1,27 @@
 use std::{
-    sync::{Arc, Mutex},
-    thread::sleep,
+    fmt::{Debug, Display},
+    future::Future,
+    iter::FusedIterator,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, TryRecvError},
+        Arc, Mutex,
+    },
+    task::{Context, Waker},
     time::{Duration, Instant},
 };
 
+use situwaition_core::{Action, Schedule};
+
 use crate::{
-    SituwaitionBase, SituwaitionError, SituwaitionOpts, SyncSituwaition, WaiterCreationError,
-    DEFAULT_SITUWAITION_CHECK_INTERVAL_MS, DEFAULT_SITUWAITION_TIMEOUT_MS,
+    builder::WaiterBuilder,
+    cancel::CancellationToken,
+    clock::{Clock, SystemClock, ThreadWaker},
+    handle::{Progress, WaitHandle},
+    observer::{CheckError, WaitObserver},
+    SituwaitionBase, SituwaitionError, SituwaitionHooks, SituwaitionOpts, SyncSituwaition,
+    WaitReport, WaiterCreationError,
 };
 
 /// Synchronous situwaitioner
@@ -22,6 +37,9 @@ where
 
     /// Function that can be run to decide whether the executor should finish
     check_fn: Option<Box<F>>,
+
+    /// Clock used to measure time and sleep between attempts
+    clock: Arc<dyn Clock>,
 }
 
 impl<R, E, F> SituwaitionBase for SyncWaiter<R, E, F>
@@ -49,82 +67,218 @@ where
 impl<R, E, F> SyncSituwaition for SyncWaiter<R, E, F>
 where
     R: Send + 'static,
-    E: Send + 'static,
+    E: Debug + Display + Send + 'static,
     F: Fn() -> Result<R, E> + Send + Sync + 'static,
 {
     fn exec(&mut self) -> Result<R, SituwaitionError<E>> {
-        let start = Instant::now();
+        self.run(false).0
+    }
+
+    fn exec_with_report(&mut self) -> (Result<R, SituwaitionError<E>>, WaitReport) {
+        let (result, report) = self.run(true);
+        (result, report.unwrap_or_default())
+    }
+}
+
+impl<R, E, F> SyncWaiter<R, E, F>
+where
+    R: Send + 'static,
+    E: Debug + Display + Send + 'static,
+    F: Fn() -> Result<R, E> + Send + Sync + 'static,
+{
+    /// Run the situwaition (with any process-wide scaling applied), optionally recording a report
+    fn run(&mut self, report: bool) -> (Result<R, SituwaitionError<E>>, Option<WaitReport>) {
+        let (opts, timeout_scale) = self.opts.scaled_with_factor();
+        let observer = WaitObserver::new(&opts).with_timeout_scale(timeout_scale);
+        let observer = match report {
+            true => observer.with_report(&opts),
+            false => observer,
+        };
+        let observer = Arc::new(observer);
+
+        let result = self.run_with_observer(&opts, observer.clone(), Arc::default());
+        (result, observer.report())
+    }
+
+    /// Run the situwaition on a background thread, returning a handle to it
+    ///
+    /// The handle can be used to check on the situwaition without blocking, to wait for it
+    /// (optionally with a timeout), to follow its progress and to cancel it (see [`WaitHandle`]).
+    pub fn spawn(mut self) -> WaitHandle<R, E> {
+        let (opts, timeout_scale) = self.opts.scaled_with_factor();
+        let progress = Arc::new(Mutex::new(Progress::default()));
+        let observer = WaitObserver::new(&opts)
+            .with_timeout_scale(timeout_scale)
+            .with_progress(progress.clone());
+        let observer = Arc::new(observer);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let (result_tx, result_rx) = mpsc::channel();
+
+        let thread_cancelled = cancelled.clone();
+        let thread = std::thread::spawn(move || {
+            let result = self.run_with_observer(&opts, observer, thread_cancelled);
+            let _ = result_tx.send(result);
+        });
+
+        WaitHandle::new(result_rx, progress, cancelled, thread.thread().clone())
+    }
+
+    /// Run the situwaition with the given options, reporting progress to the given observer
+    ///
+    /// The check function is run on a separate thread, which decides the outcome of every attempt
+    /// (sleeping for the cooldown and check interval between attempts on the waiter's clock, like the async
+    /// executors do, but never past the timeout). The calling thread only
+    /// waits for that outcome (parked on the waiter's clock), and gives up with [`SituwaitionError::CheckTimeoutError`]
+    /// if a check is still running once the timeout has passed, or with [`SituwaitionError::Cancelled`] once `cancelled`
+    /// is set (unparking the calling thread).
+    fn run_with_observer(
+        &mut self,
+        opts: &SituwaitionOpts,
+        observer: Arc<WaitObserver>,
+        cancelled: Arc<AtomicBool>,
+    ) -> Result<R, SituwaitionError<E>> {
+        let clock = self.clock.clone();
+        let start = clock.now();
 
         let check_fn = self
             .check_fn
             .take()
             .ok_or_else(|| SituwaitionError::UnexpectedError("no check fn specified".into()))?;
 
-        let result = Mutex::new(None);
-        let result_read = Arc::new(result);
-        let result_write = result_read.clone();
-
-        let err: Mutex<Option<SituwaitionError<E>>> = Mutex::new(None);
-        let err_read = Arc::new(err);
-        let err_write = err_read.clone();
+        // Whether a check is currently running
+        let check_running = Arc::new(AtomicBool::new(false));
+        // Set once the calling thread has given up (or the situwaition was cancelled), so the check thread stops
+        let (outcome_tx, outcome_rx) = mpsc::channel::<Result<R, E>>();
 
-        let timeout = self.opts.timeout;
-        let check_cooldown = self.opts.check_cooldown;
+        // The check thread unparks the calling thread once it is done
+        let caller = std::thread::current();
+        let worker_clock = clock.clone();
+        let worker_check_running = check_running.clone();
+        let timeout = opts.timeout;
+        let worker_cancelled = cancelled.clone();
+        let worker_observer = observer.clone();
+        let mut schedule = Schedule::new(opts.policy());
 
-        // We run the check function in a scoped thread in order to ensure
+        // We run the check function in a separate thread in order to ensure
         // that we can handle the case where the check function never returns in time
-        std::thread::spawn(move || loop {
-            let res = check_fn();
-            match res {
-                Ok(v) => {
-                    let mut mutex = result_write.lock().map_err(|_| ())?;
-                    *mutex = Some(v);
-                    break Ok::<(), ()>(());
-                }
-                Err(e) => {
-                    let now = Instant::now();
-                    if now - start > timeout {
-                        let mut mutex = err_write.lock().map_err(|_| ())?;
-                        *mutex = Some(SituwaitionError::TimeoutError(e));
-                        break Ok::<(), ()>(());
-                    }
+        std::thread::spawn(move || {
+            let clock = worker_clock;
+            let since_start = || clock.now().saturating_duration_since(start);
+            let mut failure = None;
+
+            worker_observer.in_scope(|| {
+                while !worker_cancelled.load(Ordering::SeqCst) {
+                    let now = since_start();
+                    match schedule.next(now) {
+                        // Like the calling thread, no check is started once the timeout has passed
+                        Action::Check { .. } if now > timeout && failure.is_some() => {
+                            if let Some(e) = failure.take() {
+                                let _ = outcome_tx.send(Err(e));
+                            }
+                            return;
+                        }
+
+                        Action::Check { .. } => {
+                            worker_observer.attempt_started(schedule.elapsed(now));
+                            worker_check_running.store(true, Ordering::SeqCst);
+                            let res = check_fn();
+                            worker_check_running.store(false, Ordering::SeqCst);
+
+                            match res {
+                                Ok(v) => {
+                                    schedule.succeeded();
+                                    let _ = outcome_tx.send(Ok(v));
+                                    return;
+                                }
+                                Err(e) => {
+                                    schedule.failed();
+                                    worker_observer.attempt_failed(
+                                        schedule.elapsed(since_start()),
+                                        &CheckError(&e),
+                                    );
+                                    failure = Some(e);
+                                }
+                            }
+                        }
 
-                    // If a cooldown was specified, wait a bit
-                    if let Some(t) = check_cooldown {
-                        sleep(t);
+                        // Cooldown after a failed check, or the interval before the next one
+                        // (cut short at the timeout, so that the failure is reported right away)
+                        Action::Sleep(d) => {
+                            let remaining = timeout.saturating_sub(since_start());
+                            clock.sleep(d.min(remaining + DEADLINE_SLACK));
+                        }
+
+                        Action::TimedOut => {
+                            if let Some(e) = failure.take() {
+                                let _ = outcome_tx.send(Err(e));
+                            }
+                            return;
+                        }
+
+                        Action::Done => return,
                     }
                 }
-            }
+            });
+            drop(outcome_tx);
+            caller.unpark();
         });
 
-        // Wait until the handle is finished, without being blocked by it
-        loop {
-            // Sleep & check for timeout until finished
-            match (result_read.lock(), err_read.lock()) {
-                (Ok(mut r), Ok(mut err)) => {
-                    // If an error has returned, it *must* be the timeout error, return that quickly
-                    if err.is_some() {
-                        return Err((*err).take().unwrap());
-                    }
+        // Cancelling the token unparks the calling thread, like the check thread finishing does
+        let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+        let mut cancellation = opts.cancellation.as_ref().map(|t| t.cancelled());
 
-                    // If we've timed out otherwise while doing the check, we timed out *during* a check
-                    if Instant::now() - start > self.opts.timeout {
-                        return Err(SituwaitionError::CheckTimeoutError);
-                    }
+        // Wait for the outcome, checking periodically (and at the timeout) whether a check is running too long,
+        // with every wait measured on the waiter's clock
+        loop {
+            match outcome_rx.try_recv() {
+                Ok(Ok(v)) => {
+                    observer.succeeded(clock.now().saturating_duration_since(start));
+                    return Ok(v);
+                }
+                Ok(Err(e)) => {
+                    return Err(observer.timed_out(
+                        clock.now().saturating_duration_since(start),
+                        SituwaitionError::TimeoutError(e, Default::default()),
+                    ));
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) if cancelled.load(Ordering::SeqCst) => {}
+                Err(TryRecvError::Disconnected) => {
+                    return Err(SituwaitionError::UnexpectedError(
+                        "check thread stopped unexpectedly".into(),
+                    ));
+                }
+            }
 
-                    // If a result came through, we can return that happily
-                    if r.is_some() {
-                        return Ok((*r).take().unwrap());
-                    }
+            if let Some(cancellation) = cancellation.as_mut() {
+                let mut cx = Context::from_waker(&waker);
+                if Pin::new(cancellation).poll(&mut cx).is_ready() {
+                    cancelled.store(true, Ordering::SeqCst);
                 }
-                _ => panic!("failed to lock mutex"),
             }
-            // Sleep before checking again
-            sleep(self.opts.check_interval);
+            if cancelled.load(Ordering::SeqCst) {
+                return Err(observer.cancelled(clock.now().saturating_duration_since(start)));
+            }
+
+            let now = clock.now();
+            let elapsed = now.saturating_duration_since(start);
+            if elapsed > opts.timeout && check_running.load(Ordering::SeqCst) {
+                cancelled.store(true, Ordering::SeqCst);
+                return Err(observer.timed_out(
+                    elapsed,
+                    SituwaitionError::CheckTimeoutError(Default::default()),
+                ));
+            }
+
+            let remaining = opts.timeout.saturating_sub(elapsed);
+            clock.park_until(now + opts.check_interval.min(remaining + DEADLINE_SLACK));
         }
     }
 }
 
+/// How far past the timeout to wait, so that the timeout has certainly passed when waking up
+const DEADLINE_SLACK: Duration = Duration::from_millis(1);
+
 #[allow(dead_code)]
 impl<R, E, F> SyncWaiter<R, E, F>
 where
@@ -136,6 +290,7 @@ where
         SyncWaiter {
             opts: SituwaitionOpts::default(),
             check_fn: Some(Box::new(check_fn)),
+            clock: Arc::new(SystemClock),
         }
     }
 
@@ -144,23 +299,42 @@ where
         SyncWaiter {
             opts,
             check_fn: Some(Box::new(check_fn)),
+            clock: Arc::new(SystemClock),
         }
     }
 
+    /// Use a different clock to measure time and sleep (see [`crate::clock`])
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Set the name of the situwaition (see [`SituwaitionOpts::name`])
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.opts.name = Some(name.into());
+        self
+    }
+
+    /// Replace the lifecycle hooks that will be run during the situwaition
+    pub fn with_hooks(mut self, hooks: SituwaitionHooks) -> Self {
+        self.opts.hooks = hooks;
+        self
+    }
+
+    /// Stop the situwaition when the given token is cancelled (see [`crate::cancel`])
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.opts.cancellation = Some(token);
+        self
+    }
+
+    /// Start a validating builder for a SyncWaiter (see [`crate::builder`])
+    pub fn builder(check_fn: F) -> WaiterBuilder<F, Self> {
+        WaiterBuilder::new(check_fn, Self::with_opts)
+    }
+
     /// Create a SyncWaiter with only timeout customized
     pub fn with_timeout(check_fn: F, timeout: Duration) -> Result<Self, WaiterCreationError> {
-        if timeout < Duration::from_millis(DEFAULT_SITUWAITION_CHECK_INTERVAL_MS) {
-            return Err(WaiterCreationError::InvalidTimeout(
-                format!("supplied timeout ({}ms) is shorter the default timeout ({DEFAULT_SITUWAITION_CHECK_INTERVAL_MS}ms)", timeout.as_millis())
-            ));
-        }
-        Ok(Self::with_opts(
-            check_fn,
-            SituwaitionOpts {
-                timeout,
-                ..SituwaitionOpts::default()
-            },
-        ))
+        Self::builder(check_fn).timeout(timeout).build()
     }
 
     /// Create a SyncWaiter with only check interval customized
@@ -168,21 +342,104 @@ where
         check_fn: F,
         check_interval: Duration,
     ) -> Result<Self, WaiterCreationError> {
-        if check_interval > Duration::from_millis(DEFAULT_SITUWAITION_TIMEOUT_MS) {
-            return Err(WaiterCreationError::InvalidTimeout(
-                format!("supplied check interval ({}ms) is larger than the default timeout ({DEFAULT_SITUWAITION_TIMEOUT_MS}ms)", check_interval.as_millis())
-            ));
+        Self::builder(check_fn)
+            .check_interval(check_interval)
+            .build()
+    }
+
+    /// Drive the situwaition manually, one attempt per call to [`Iterator::next`].
+    ///
+    /// The check function is run on the calling thread, and the iterator sleeps between attempts
+    /// the same way the async executors do (the check cooldown, followed by the check interval).
+    /// Iteration ends after the first successful attempt, or once the timeout has elapsed.
+    ///
+    /// Note that since the check runs on the calling thread, a check that never returns cannot be
+    /// interrupted -- use [`SyncSituwaition::exec`] if you need [`SituwaitionError::CheckTimeoutError`].
+    pub fn attempts(&self) -> SyncAttempts<'_, R, E, F> {
+        SyncAttempts {
+            waiter: self,
+            schedule: Schedule::new(self.opts.scaled().policy()),
+            origin: None,
         }
-        Ok(Self::with_opts(
-            check_fn,
-            SituwaitionOpts {
-                check_interval,
-                ..SituwaitionOpts::default()
-            },
-        ))
     }
 }
 
+/// Iterator over the attempts of a [`SyncWaiter`], created with [`SyncWaiter::attempts`]
+pub struct SyncAttempts<'a, R, E, F>
+where
+    R: Send,
+    E: Send + 'static,
+    F: Fn() -> Result<R, E> + Send + Sync + 'static,
+{
+    /// The waiter whose check function drives the attempts
+    waiter: &'a SyncWaiter<R, E, F>,
+
+    /// Schedule for the attempts (with any process-wide scaling applied)
+    schedule: Schedule,
+
+    /// When iteration started, which times given to the schedule are measured from
+    origin: Option<Instant>,
+}
+
+impl<'a, R, E, F> SyncAttempts<'a, R, E, F>
+where
+    R: Send,
+    E: Send + 'static,
+    F: Fn() -> Result<R, E> + Send + Sync + 'static,
+{
+    /// Time since iteration started, as measured by the waiter's clock
+    fn since_origin(&self) -> Duration {
+        self.origin
+            .map(|o| self.waiter.clock.now().saturating_duration_since(o))
+            .unwrap_or_default()
+    }
+
+    /// Time elapsed since the first attempt was started
+    pub fn elapsed(&self) -> Duration {
+        self.schedule.elapsed(self.since_origin())
+    }
+}
+
+impl<'a, R, E, F> Iterator for SyncAttempts<'a, R, E, F>
+where
+    R: Send,
+    E: Send + 'static,
+    F: Fn() -> Result<R, E> + Send + Sync + 'static,
+{
+    type Item = Result<R, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let check_fn = self.waiter.check_fn.as_deref()?;
+        let clock = &self.waiter.clock;
+        self.origin.get_or_insert_with(|| clock.now());
+
+        // Every attempt after the first is preceded by the cooldown and interval,
+        // unless the timeout has been reached in the meantime
+        loop {
+            match self.schedule.next(self.since_origin()) {
+                Action::Check { .. } => {
+                    let result = check_fn();
+                    match result {
+                        Ok(_) => self.schedule.succeeded(),
+                        Err(_) => self.schedule.failed(),
+                    }
+                    return Some(result);
+                }
+                Action::Sleep(d) => clock.sleep(d),
+                Action::TimedOut | Action::Done => return None,
+            }
+        }
+    }
+}
+
+impl<'a, R, E, F> FusedIterator for SyncAttempts<'a, R, E, F>
+where
+    R: Send,
+    E: Send + 'static,
+    F: Fn() -> Result<R, E> + Send + Sync + 'static,
+{
+}
+
 /////////////////////
 // Implementations //
 /////////////////////
@@ -201,10 +458,18 @@ where
     SyncWaiter::from_fn(check_fn).exec()
 }
 
-#[cfg(all(test, not(any(feature = "async-std", feature = "tokio"))))]
+#[cfg(all(
+    test,
+    not(any(feature = "async-std", feature = "tokio", feature = "smol"))
+))]
 mod tests {
     use std::io::ErrorKind;
 
+    use crate::{
+        clock::MockClock,
+        report::{AttemptOutcome, WaitOutcome},
+    };
+
     use super::*;
 
     #[test]
@@ -235,7 +500,7 @@ mod tests {
             )
             .expect("failed to create")
             .exec(),
-            Err(SituwaitionError::TimeoutError(std::io::Error { .. })),
+            Err(SituwaitionError::TimeoutError(std::io::Error { .. }, _)),
         ),);
     }
 
@@ -266,7 +531,7 @@ mod tests {
                 )
                 .expect("failed to create")
                 .exec(),
-                Err(SituwaitionError::TimeoutError(std::io::Error { .. })),
+                Err(SituwaitionError::TimeoutError(std::io::Error { .. }, _)),
             ),
             "always erroring check fails in 100ms with timeout of 100ms"
         );
@@ -276,6 +541,95 @@ mod tests {
         );
     }
 
+    /// A check that hangs is abandoned at the timeout, even if it started just before it
+    #[test]
+    fn test_unit_sync_check_timeout_worst_case() {
+        let start = Instant::now();
+        let result = SyncWaiter::with_opts(
+            move || {
+                if start.elapsed() >= Duration::from_millis(250) {
+                    std::thread::sleep(Duration::from_secs(2));
+                }
+                Err::<(), _>(std::io::Error::new(ErrorKind::Other, "not ready"))
+            },
+            SituwaitionOpts {
+                timeout: Duration::from_millis(300),
+                check_interval: Duration::from_millis(20),
+                ..SituwaitionOpts::builtin()
+            },
+        )
+        .exec();
+
+        assert!(
+            matches!(result, Err(SituwaitionError::CheckTimeoutError(_))),
+            "hanging check times out"
+        );
+        let elapsed = start.elapsed();
+        assert!(
+            elapsed >= Duration::from_millis(300),
+            "check timed out before the timeout"
+        );
+        assert!(
+            elapsed < Duration::from_millis(400),
+            "exec took {elapsed:?}, well past the 300ms timeout"
+        );
+    }
+
+    /// The interval after a failed check is cut short at the timeout
+    #[test]
+    fn test_unit_sync_timeout_worst_case() {
+        let start = Instant::now();
+        let result = SyncWaiter::with_opts(
+            || Err::<(), _>(std::io::Error::new(ErrorKind::Other, "not ready")),
+            SituwaitionOpts {
+                timeout: Duration::from_millis(300),
+                check_interval: Duration::from_millis(200),
+                ..SituwaitionOpts::builtin()
+            },
+        )
+        .exec();
+
+        assert!(
+            matches!(result, Err(SituwaitionError::TimeoutError(..))),
+            "failing check times out"
+        );
+        let elapsed = start.elapsed();
+        assert!(
+            elapsed < Duration::from_millis(380),
+            "exec took {elapsed:?}, well past the 300ms timeout"
+        );
+    }
+
+    /// Like the async executors, a failed check is followed by the check interval
+    #[test]
+    fn test_unit_sync_executor_waits_check_interval() {
+        let clock = MockClock::new();
+        let check_clock = clock.clone();
+        let started = Arc::new(Mutex::new(Vec::new()));
+        let shared_started = started.clone();
+
+        let result = SyncWaiter::with_opts(
+            move || {
+                shared_started.lock().unwrap().push(check_clock.elapsed());
+                Err::<(), _>(std::io::Error::new(ErrorKind::Other, "not ready"))
+            },
+            SituwaitionOpts {
+                timeout: Duration::from_secs(300),
+                check_interval: Duration::from_secs(100),
+                ..SituwaitionOpts::builtin()
+            },
+        )
+        .with_clock(clock)
+        .exec();
+
+        assert!(matches!(result, Err(SituwaitionError::TimeoutError(..))));
+        assert_eq!(
+            *started.lock().unwrap(),
+            [0, 100, 200, 300].map(Duration::from_secs),
+            "checks are started one check interval apart"
+        );
+    }
+
     #[test]
     fn test_unit_sync_executor_with_check_interval() {
         let start = Instant::now();
@@ -297,6 +651,184 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_unit_sync_attempts_until_success() {
+        let count = Arc::new(Mutex::new(0));
+        let shared_count = count.clone();
+        let waiter = SyncWaiter::with_check_interval(
+            move || {
+                let mut count = shared_count.lock().expect("failed to lock");
+                *count += 1;
+                match *count {
+                    3 => Ok(*count),
+                    _ => Err(std::io::Error::new(ErrorKind::Other, "test")),
+                }
+            },
+            Duration::from_millis(10),
+        )
+        .expect("failed to create");
+
+        let results = waiter.attempts().collect::<Vec<_>>();
+        assert_eq!(
+            results.len(),
+            3,
+            "iteration stopped after the first success"
+        );
+        assert!(matches!(results.last(), Some(Ok(3))), "last attempt passed");
+    }
+
+    #[test]
+    fn test_unit_sync_attempts_until_timeout() {
+        let start = Instant::now();
+        let waiter = SyncWaiter::with_opts(
+            || Err::<(), std::io::Error>(std::io::Error::new(ErrorKind::Other, "test")),
+            SituwaitionOpts {
+                timeout: Duration::from_millis(250),
+                check_interval: Duration::from_millis(50),
+                ..SituwaitionOpts::default()
+            },
+        );
+
+        let mut attempts = waiter.attempts();
+        assert!(
+            attempts.by_ref().all(|r| r.is_err()),
+            "always erroring check yields only errors"
+        );
+        assert!(attempts.next().is_none(), "iteration stays finished");
+        assert!(
+            Instant::now() - start >= Duration::from_millis(250),
+            "iteration ended after the timeout"
+        );
+    }
+
+    #[test]
+    fn test_unit_sync_attempts_non_error() {
+        // Error types that do not implement `std::error::Error` can still be waited on manually
+        let waiter = SyncWaiter::with_check_interval(
+            || Err::<(), &str>("not ready"),
+            Duration::from_millis(10),
+        )
+        .expect("failed to create");
+        assert_eq!(waiter.attempts().next(), Some(Err("not ready")));
+    }
+
+    #[test]
+    fn test_unit_sync_executor_non_error() {
+        // Error types that do not implement `std::error::Error` can be waited on, and are passed to hooks
+        let failures = Arc::new(Mutex::new(Vec::new()));
+        let shared_failures = failures.clone();
+        let result = SyncWaiter::with_opts(
+            || Err::<(), &str>("not ready"),
+            SituwaitionOpts {
+                timeout: Duration::from_millis(50),
+                check_interval: Duration::from_millis(10),
+                ..SituwaitionOpts::builtin()
+            },
+        )
+        .with_hooks(
+            SituwaitionHooks::new()
+                .on_failure(move |_, err| shared_failures.lock().unwrap().push(err.to_string())),
+        )
+        .exec();
+
+        assert!(matches!(
+            result,
+            Err(SituwaitionError::TimeoutError("not ready", _))
+        ));
+        let failures = failures.lock().unwrap();
+        assert!(!failures.is_empty(), "failure hook was run");
+        assert!(failures.iter().all(|e| e == "not ready"));
+    }
+
+    #[test]
+    fn test_unit_sync_executor_hooks() {
+        let attempts = Arc::new(Mutex::new(0));
+        let failures = Arc::new(Mutex::new(0));
+        let (shared_attempts, shared_failures) = (attempts.clone(), failures.clone());
+
+        let (result, report) = SyncWaiter::with_timeout(
+            || Err::<(), std::io::Error>(std::io::Error::new(ErrorKind::Other, "test")),
+            Duration::from_millis(300),
+        )
+        .expect("failed to create")
+        .with_hooks(
+            SituwaitionHooks::new()
+                .on_attempt(move |_| *shared_attempts.lock().unwrap() += 1)
+                .on_failure(move |_, _| *shared_failures.lock().unwrap() += 1)
+                .on_timeout(|info| Some(format!("gave up after {} attempts", info.attempt))),
+        )
+        .exec_with_report();
+
+        let attempts = *attempts.lock().unwrap();
+        assert!(attempts > 0, "attempt hook was run");
+        assert_eq!(
+            attempts,
+            *failures.lock().unwrap(),
+            "failure hook was run for every attempt"
+        );
+        assert_eq!(
+            report.diagnostics,
+            Some(format!("gave up after {attempts} attempts")),
+            "timeout hook diagnostics were recorded in the report"
+        );
+        let err = result.expect_err("failing check times out");
+        assert!(
+            matches!(
+                err,
+                SituwaitionError::TimeoutError(std::io::Error { .. }, _)
+            ),
+            "error is still a timeout"
+        );
+        assert_eq!(
+            err.diagnostics(),
+            report.diagnostics.as_deref(),
+            "timeout hook diagnostics were attached to the error"
+        );
+        assert!(
+            err.to_string()
+                .ends_with(&format!("\ngave up after {attempts} attempts")),
+            "diagnostics are shown with the error"
+        );
+    }
+
+    #[test]
+    fn test_unit_sync_executor_exec_with_report() {
+        let (result, report) = SyncWaiter::with_opts(
+            || {
+                std::thread::sleep(Duration::from_millis(20));
+                Err::<(), std::io::Error>(std::io::Error::new(ErrorKind::Other, "test"))
+            },
+            SituwaitionOpts {
+                timeout: Duration::from_millis(300),
+                check_interval: Duration::from_millis(100),
+                check_cooldown: Some(Duration::from_millis(30)),
+                ..SituwaitionOpts::default()
+            },
+        )
+        .with_name("report")
+        .exec_with_report();
+
+        assert!(matches!(result, Err(SituwaitionError::TimeoutError(..))));
+        assert_eq!(report.name.as_deref(), Some("report"));
+        assert_eq!(
+            report.outcome,
+            WaitOutcome::TimedOut,
+            "report shows timeout"
+        );
+        assert!(report.attempts.len() > 1, "report contains every attempt");
+        assert!(
+            report
+                .attempts
+                .iter()
+                .all(|a| a.outcome == AttemptOutcome::Failed && a.error.as_deref() == Some("test")),
+            "attempts are recorded as failures"
+        );
+        assert!(
+            report.p50_check_latency() >= Some(Duration::from_millis(20)),
+            "check latency is recorded"
+        );
+    }
+
     #[test]
     fn test_unit_sync_executor_with_long_check() {
         let start = Instant::now();
@@ -311,7 +843,7 @@ mod tests {
                 )
                 .expect("failed to create")
                 .exec(),
-                Err(SituwaitionError::CheckTimeoutError),
+                Err(SituwaitionError::CheckTimeoutError(_)),
             ),
             "check that finishes in 500ms times out in 100ms as configured"
         );
@@ -0,0 +1,72 @@
+//! Process-wide defaults are shared by every test in a binary, so they are only changed here
+
+use std::{
+    io::{Error, ErrorKind},
+    time::{Duration, Instant},
+};
+
+use situwaition::{
+    defaults::{self, Preset},
+    runtime::AsyncWaiter,
+    sync::SyncWaiter,
+    wait_for, SituwaitionBase, SituwaitionError, SituwaitionOpts,
+};
+
+fn assert_fast_unit_test(opts: &SituwaitionOpts, source: &str) {
+    assert_eq!(
+        opts.timeout,
+        Duration::from_millis(500),
+        "{source} uses the preset timeout"
+    );
+    assert_eq!(
+        opts.check_interval,
+        Duration::from_millis(10),
+        "{source} uses the preset check interval"
+    );
+}
+
+#[test]
+fn test_int_preset_applies_to_new_waiters() {
+    defaults::set_preset(Preset::FastUnitTest);
+
+    assert_fast_unit_test(&SituwaitionOpts::default(), "SituwaitionOpts::default");
+    assert_fast_unit_test(
+        SyncWaiter::from_fn(|| Ok::<_, Error>(())).options(),
+        "SyncWaiter::from_fn",
+    );
+    assert_fast_unit_test(
+        AsyncWaiter::from_factory(|| async { Ok::<_, Error>(()) }).options(),
+        "AsyncWaiter::from_factory",
+    );
+
+    let started = Instant::now();
+    let result = wait_for(|| Err::<(), _>(Error::new(ErrorKind::Other, "not ready")));
+    assert!(
+        matches!(result, Err(SituwaitionError::TimeoutError(..))),
+        "wait_for times out"
+    );
+    assert!(
+        started.elapsed() < Duration::from_secs(2),
+        "wait_for uses the preset timeout"
+    );
+
+    defaults::set_default_opts(SituwaitionOpts {
+        name: Some("db".into()),
+        ..Preset::IntegrationTest.opts()
+    })
+    .expect("valid defaults");
+    let opts = SituwaitionOpts::default();
+    assert_eq!(opts.timeout, Duration::from_secs(30));
+    assert!(opts.name.is_none(), "names are not shared by default");
+
+    defaults::reset();
+    let opts = SituwaitionOpts::default();
+    assert_eq!(
+        (opts.timeout, opts.check_interval),
+        (
+            SituwaitionOpts::builtin().timeout,
+            SituwaitionOpts::builtin().check_interval
+        ),
+        "builtin defaults are restored"
+    );
+}
@@ -0,0 +1,45 @@
+//! The timeout scale factor is shared by every test in a binary, so it is only changed here
+
+use std::{
+    io::{Error, ErrorKind},
+    time::Duration,
+};
+
+use situwaition::{scale, sync::SyncWaiter, SituwaitionOpts, SyncSituwaition};
+
+#[test]
+fn test_int_timeout_scale_in_errors() {
+    scale::set_timeout_scale(2.0);
+
+    // The scale factor is changed while the situwaition is running, which must not affect it
+    let (result, report) = SyncWaiter::with_opts(
+        || {
+            scale::reset();
+            Err::<(), _>(Error::new(ErrorKind::Other, "not ready"))
+        },
+        SituwaitionOpts {
+            timeout: Duration::from_millis(50),
+            check_interval: Duration::from_millis(10),
+            ..SituwaitionOpts::builtin()
+        },
+    )
+    .exec_with_report();
+    assert_eq!(
+        scale::timeout_scale(),
+        1.0,
+        "scaling was reset during the wait"
+    );
+
+    let err = result.expect_err("failing check times out");
+    assert_eq!(err.timeout_scale(), Some(2.0));
+    assert_eq!(
+        err.to_string(),
+        "failed repeatedly until the timeout: not ready (timeouts scaled by 2x)",
+        "the scale factor the situwaition started with is shown"
+    );
+    assert_eq!(report.timeout_scale, Some(2.0));
+    assert!(
+        report.elapsed >= Duration::from_millis(100),
+        "the scaled timeout was used"
+    );
+}